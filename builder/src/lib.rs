@@ -2,32 +2,25 @@ use proc_macro::TokenStream;
 use std::error::Error;
 use std::fmt;
 use quote::{quote, quote_spanned};
-use syn::{parse_macro_input, DeriveInput, Ident, Data, Fields, Type, PathArguments, Field, GenericArgument, Meta, NestedMeta, Lit};
+use syn::{parenthesized, parse_macro_input, Attribute, DeriveInput, Expr, ExprClosure, Ident, Data, Fields, Type, PathArguments, Field, GenericArgument, Meta, NestedMeta, Lit, LitStr, Pat, Token};
+use syn::parse::ParseStream;
 use syn::spanned::Spanned;
 use syn::__private::{Span, TokenStream2};
 
 fn get_inner_type<'a>(f: &'a Field, type_name: &str) -> Option<&'a GenericArgument> {
     let ty = &f.ty;
     match ty {
-        Type::Path(ref type_path) => {
-            if type_path.path.segments.len() == 1 {
-                //TODO fix calls to unwrap
-                let path_segment = &type_path.path.segments.first().unwrap();
-                let ident = &path_segment.ident;
-                if ident == type_name {
-                    match &path_segment.arguments {
-                        PathArguments::AngleBracketed(args) => {
-                            if args.args.len() == 1 {
-                                let ty = args.args.first().unwrap();
-                                Some(ty)
-                            } else {
-                                None
-                            }
-                        }
-                        _ => { None }
+        Type::Path(ref type_path) if type_path.path.segments.len() == 1 => {
+            //TODO fix calls to unwrap
+            let path_segment = &type_path.path.segments.first().unwrap();
+            let ident = &path_segment.ident;
+            if ident == type_name {
+                match &path_segment.arguments {
+                    PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                        let ty = args.args.first().unwrap();
+                        Some(ty)
                     }
-                } else {
-                    None
+                    _ => { None }
                 }
             } else {
                 None
@@ -37,12 +30,71 @@ fn get_inner_type<'a>(f: &'a Field, type_name: &str) -> Option<&'a GenericArgume
     }
 }
 
-fn get_build_method(data: &Data, name: &Ident) -> TokenStream2 {
+/// Options given on the struct itself through `#[builder(...)]`.
+#[derive(Default)]
+struct ContainerOptions {
+    /// Expression evaluated (with `Self` being the built type) to supply
+    /// any field left unset when `build()` is called.
+    default_from: Option<Expr>,
+    /// Generate `from_args` for populating the builder from command line
+    /// flags. Only accepted with the `cli` feature enabled.
+    cli: bool,
+    /// Prefix of the environment variables read by `with_env`.
+    env_prefix: Option<String>,
+    /// Separator between items of a `Vec` field in an environment variable.
+    env_separator: Option<String>,
+}
+
+fn get_container_options(attrs: &[Attribute]) -> syn::Result<ContainerOptions> {
+    let mut options = ContainerOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("builder")) {
+        let meta = match attr.parse_meta()? {
+            Meta::List(meta) => meta,
+            meta => return Err(syn::Error::new_spanned(meta, "expected `builder(...)`")),
+        };
+        for nested in meta.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident("default_from") => {
+                    match &pair.lit {
+                        Lit::Str(expr) => options.default_from = Some(expr.parse()?),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a string containing an expression")),
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident("env_prefix") || pair.path.is_ident("env_separator") => {
+                    let value = match &pair.lit {
+                        Lit::Str(value) => value.value(),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a string")),
+                    };
+                    if pair.path.is_ident("env_prefix") {
+                        options.env_prefix = Some(value);
+                    } else {
+                        options.env_separator = Some(value);
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("cli") => {
+                    if !cfg!(feature = "cli") {
+                        return Err(syn::Error::new_spanned(path, "`builder(cli)` requires the `cli` feature of derive_builder"));
+                    }
+                    options.cli = true;
+                }
+                nested => {
+                    return Err(syn::Error::new_spanned(nested, "expected one of `default_from`, `cli`, `env_prefix` or `env_separator`"));
+                }
+            }
+        }
+    }
+    Ok(options)
+}
+
+fn get_build_method(data: &Data, name: &Ident, options: &ContainerOptions, field_options: &[FieldOptions]) -> TokenStream2 {
+    // Lazily built base instance, only created if some field is actually unset.
+    let base = Ident::new("base", Span::mixed_site());
+    let target = name;
     match *data {
         Data::Struct(ref data) => {
             match data.fields {
                 Fields::Named(ref fields) => {
-                    let check_err = fields.named.iter().map(|f| {
+                    let check_err = fields.named.iter().zip(field_options).map(|(f, field_options)| {
                         let name = &f.ident;
                         let name_string = match name {
                             None => {"".into()}
@@ -50,23 +102,46 @@ fn get_build_method(data: &Data, name: &Ident) -> TokenStream2 {
                                 format!("{}", name)
                             }
                         };
-                        let is_option_arg = get_inner_type(&f, "Option");
-                        if is_option_arg.is_none() {
+                        let from_base = quote! {
+                            #base.get_or_insert_with(<#target as BuilderDefaultFrom>::default_from).#name.clone()
+                        };
+                        let is_option_arg = get_inner_type(f, "Option");
+                        let is_repeated = field_options.each.is_some();
+                        if is_option_arg.is_some() {
+                            if options.default_from.is_some() {
+                                quote_spanned! {f.span()=>
+                                    let #name = match self.#name {
+                                        None => { #from_base },
+                                        Some(ref #name) => { Some(#name.to_owned()) }
+                                    };
+                                }
+                            } else {
+                                quote_spanned! {f.span()=>
+                                    let #name = self.#name.clone();
+                                }
+                            }
+                        } else {
+                            let unset = if options.default_from.is_some() {
+                                from_base
+                            } else if is_repeated {
+                                // Without a base instance, an `each` field that
+                                // was never given an item builds as an empty Vec
+                                // instead of failing, as tests/07 requires.
+                                quote! { Vec::new() }
+                            } else {
+                                quote! {
+                                    let mut err_string = format!("{}", #name_string);
+                                    err_string += " is unset!";
+                                    let err_string: Box<dyn ::std::error::Error> = err_string.into();
+                                    return Err(err_string);
+                                }
+                            };
                             quote_spanned! {f.span()=>
                                 let #name = match self.#name {
-                                    None => {
-                                        let mut err_string = format!("{}", #name_string);
-                                        err_string += " is unset!";
-                                        let err_string: Box<dyn ::std::error::Error> = err_string.into();
-                                        return Err(err_string);
-                                    },
+                                    None => { #unset },
                                     Some(ref #name) => { #name.to_owned() }
                                 };
                             }
-                        } else {
-                            quote_spanned! {f.span()=>
-                                let #name = self.#name.clone();
-                            }
                         }
                     });
                     let build = fields.named.iter().map(|f| {
@@ -75,8 +150,14 @@ fn get_build_method(data: &Data, name: &Ident) -> TokenStream2 {
                             #name,
                         }
                     });
+                    let default_from = options.default_from.as_ref().map(|_| {
+                        quote! {
+                            let mut #base: Option<#name> = None;
+                        }
+                    });
                     quote! {
                         pub fn build(&mut self) -> Result<#name, Box<dyn ::std::error::Error>> {
+                            #default_from
                             #(#check_err)*
                             Ok(#name {
                                 #(#build)*
@@ -115,91 +196,167 @@ impl Error for BuilderError {
     }
 }
 
-fn get_builder_attribute_name(f: &Field) -> Option<String> {
-    if let Some(attr) = f.attrs.first() {
-        if attr.path.segments.first().unwrap().ident == "builder" {
-            match attr.parse_meta() {
-                Ok(Meta::List(meta)) => {
-                    for x in meta.nested {
-                        match x {
-                            NestedMeta::Meta(y) => {
-                                match y {
-                                    Meta::Path(_) => {}
-                                    Meta::List(_) => {}
-                                    Meta::NameValue(pair) => {
-                                        let lit = pair.lit;
-                                        match lit {
-                                            Lit::Str(partial_name) => {
-                                                return Some(partial_name.value());
-                                            }
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                            }
-                            NestedMeta::Lit(_) => {}
+/// How the setter of a field is generated.
+#[derive(Default)]
+enum Setter {
+    #[default]
+    Generated,
+    /// `setter(custom)`: the user writes the setter themselves.
+    Custom,
+    /// `setter(transform = |...| ...)`: the closure's parameters become the
+    /// setter's parameters and its body produces the stored value.
+    Transform(ExprClosure),
+}
+
+/// Options given on a single field through `#[builder(...)]`.
+#[derive(Default)]
+struct FieldOptions {
+    /// Name of the one-at-a-time setter for a `Vec` field. Such a field is
+    /// never reported as unset by `build()`; it is empty if no item was given.
+    each: Option<String>,
+    /// Environment variable read by `with_env`, overriding the name derived
+    /// from the container's `env_prefix`.
+    env: Option<String>,
+    setter: Setter,
+}
+
+fn parse_field_options(f: &Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in f.attrs.iter().filter(|attr| attr.path.is_ident("builder")) {
+        let path = &attr.path;
+        let tokens = &attr.tokens;
+        let unrecognized = || syn::Error::new_spanned(quote!(#path #tokens), "expected `builder(each = \"...\")`");
+        attr.parse_args_with(|input: ParseStream| {
+            while !input.is_empty() {
+                let key: Ident = input.parse().map_err(|_| unrecognized())?;
+                if key == "each" || key == "env" {
+                    input.parse::<Token![=]>()?;
+                    let value = input.parse::<LitStr>()?.value();
+                    if key == "each" {
+                        check_each_type(f, quote!(#path #tokens))?;
+                        options.each = Some(value);
+                    } else {
+                        options.env = Some(value);
+                    }
+                } else if key == "setter" {
+                    let content;
+                    parenthesized!(content in input);
+                    let kind: Ident = content.parse()?;
+                    if kind == "custom" {
+                        options.setter = Setter::Custom;
+                    } else if kind == "transform" {
+                        content.parse::<Token![=]>()?;
+                        let closure: ExprClosure = content.parse()?;
+                        if let Some(input) = closure.inputs.iter().find(|input| !matches!(input, Pat::Type(_))) {
+                            return Err(syn::Error::new_spanned(input, "transform parameters need a type annotation"));
                         }
+                        options.setter = Setter::Transform(closure);
+                    } else {
+                        return Err(syn::Error::new(kind.span(), "expected `setter(custom)` or `setter(transform = |...| ...)`"));
+                    }
+                } else {
+                    return Err(unrecognized());
+                }
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+/// Item type of a `Vec<T>`.
+fn get_vec_item(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(ty) if ty.path.segments.len() == 1 && ty.path.segments[0].ident == "Vec" => {
+            match &ty.path.segments[0].arguments {
+                PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                    match args.args.first() {
+                        Some(GenericArgument::Type(item)) => Some(item),
+                        _ => None,
                     }
-                },
-                _ => {
                 }
+                _ => None,
             }
         }
+        _ => None,
     }
-    None
 }
 
-fn get_builder_impl(data: &Data) -> Result<TokenStream2, BuilderError> {
+/// `each` pushes onto the stored collection, so the field must be a `Vec`.
+fn check_each_type(f: &Field, attr: TokenStream2) -> syn::Result<()> {
+    if let Some(GenericArgument::Type(_)) = get_inner_type(f, "Vec") {
+        return Ok(());
+    }
+    let option_vec = match get_inner_type(f, "Option") {
+        Some(GenericArgument::Type(inner)) => get_vec_item(inner).is_some(),
+        _ => false,
+    };
+    let msg = if option_vec {
+        "`each` cannot be used on `Option<Vec<T>>`; use `Vec<T>` instead, which is empty when no item is given"
+    } else {
+        "`each` requires a collection type: the field must be a `Vec<T>`"
+    };
+    Err(syn::Error::new_spanned(attr, msg))
+}
+
+fn get_builder_impl(data: &Data, field_options: &[FieldOptions]) -> Result<TokenStream2, BuilderError> {
     match *data {
         Data::Struct(ref data) => {
             match data.fields {
                 Fields::Named(ref fields) => {
                     let mut things = vec![];
-                    for f in fields.named.iter() {
+                    for (f, options) in fields.named.iter().zip(field_options) {
                         let name = &f.ident;
                         let ty = &f.ty;
-                        let fn_name = get_builder_attribute_name(&f);
-                        if let Some(fn_name) = fn_name {
-                            let item_name = "item_".to_string() + &fn_name;
+                        // Parameters of the setter and the value they produce.
+                        let setter = |arg: &Ident, ty: &Type| match &options.setter {
+                            Setter::Transform(closure) => {
+                                let params = closure.inputs.iter();
+                                let body = &closure.body;
+                                (quote! { #(#params),* }, quote! { #body })
+                            }
+                            _ => (quote! { #arg: #ty }, quote! { #arg }),
+                        };
+                        if let Setter::Custom = options.setter {
+                            continue;
+                        }
+                        if let Some(fn_name) = &options.each {
+                            let item_name = "item_".to_string() + fn_name;
                             let item_name = Ident::new(&item_name, Span::call_site());
-                            let fn_name = Ident::new(&fn_name, Span::call_site());
-                            match get_inner_type(&f, "Vec") {
-                                Some(GenericArgument::Type(ty)) => {
-                                    things.push(quote_spanned! {f.span()=>
-                                        fn #fn_name(&mut self, #item_name: #ty) -> &mut Self {
-                                            if let Some(ref mut #name) = self.#name {
-                                                #name.push(#item_name);
-                                            } else {
-                                                self.#name = Some(vec![#item_name]);
-                                            }
-                                            self
-                                        }});
-                                }
-                                _ => {
-                                    things.push(quote_spanned! {f.span()=>
-                                        fn #fn_name(&mut self, #item_name: #ty) -> &mut Self {
-                                            if let Some(#item_name) = self.#name {
-                                                self.name.push(#item_name);
-                                            } else {
-                                                self.#name = Some(vec![#item_name]);
-                                            }
-                                            self
-                                        }});
-                                }
+                            let fn_name = Ident::new(fn_name, Span::call_site());
+                            // `parse_field_options` has checked that this is a `Vec`.
+                            if let Some(GenericArgument::Type(ty)) = get_inner_type(f, "Vec") {
+                                let (params, item) = setter(&item_name, ty);
+                                things.push(quote_spanned! {f.span()=>
+                                    fn #fn_name(&mut self, #params) -> &mut Self {
+                                        let #item_name = #item;
+                                        if let Some(ref mut #name) = self.#name {
+                                            #name.push(#item_name);
+                                        } else {
+                                            self.#name = Some(vec![#item_name]);
+                                        }
+                                        self
+                                    }});
                             }
                         } else {
-                            match get_inner_type(&f, "Option") {
+                            let arg = name.as_ref().unwrap();
+                            match get_inner_type(f, "Option") {
                                 Some(GenericArgument::Type(ty)) => {
+                                    let (params, value) = setter(arg, ty);
                                     things.push(quote_spanned! {f.span()=>
-                                    fn #name(&mut self, #name: #ty) -> &mut Self {
-                                        self.#name = Some(#name);
+                                    fn #name(&mut self, #params) -> &mut Self {
+                                        self.#name = Some(#value);
                                         self
                                     }})
                                 },
                                 _ => {
+                                    let (params, value) = setter(arg, ty);
                                     things.push(quote_spanned! {f.span()=>
-                                    fn #name(&mut self, #name: #ty) -> &mut Self {
-                                        self.#name = Some(#name);
+                                    fn #name(&mut self, #params) -> &mut Self {
+                                        self.#name = Some(#value);
                                         self
                                     }})
                                 }
@@ -221,6 +378,178 @@ fn get_builder_impl(data: &Data) -> Result<TokenStream2, BuilderError> {
     }
 }
 
+/// Converts `value`, a string read by `from_args` or `with_env` (named by
+/// `source` in errors), into a value of type `ty` with FromStr, unless the
+/// setter transforms it. A transform is called like the setter would be, its
+/// single parameter being the string itself if it is a `&str` and parsed
+/// with FromStr otherwise. `invalid` handles a parse error `err`.
+fn from_str(setter: &Setter, ty: &Type, value: TokenStream2, invalid: &TokenStream2, source: &str) -> syn::Result<TokenStream2> {
+    let parse = |ty: &Type| quote! {
+        match #value.parse::<#ty>() {
+            Ok(value) => value,
+            Err(err) => { #invalid }
+        }
+    };
+    let closure = match setter {
+        Setter::Transform(closure) => closure,
+        _ => return Ok(parse(ty)),
+    };
+    let param = match closure.inputs.first() {
+        Some(Pat::Type(param)) if closure.inputs.len() == 1 => param,
+        _ => {
+            let msg = format!("{} can only call a transform taking a single `&str` or FromStr parameter", source);
+            return Err(syn::Error::new_spanned(&closure.inputs, msg));
+        }
+    };
+    let arg = match &*param.ty {
+        Type::Reference(reference) if matches!(&*reference.elem, Type::Path(path) if path.path.is_ident("str")) => {
+            quote!(&*#value)
+        }
+        ty => parse(ty),
+    };
+    let pat = &param.pat;
+    let param_ty = &param.ty;
+    let body = &closure.body;
+    Ok(quote! {{
+        let #pat: #param_ty = #arg;
+        #body
+    }})
+}
+
+fn get_from_args_method(data: &Data, name: &Ident, field_options: &[FieldOptions]) -> syn::Result<TokenStream2> {
+    let builder = Ident::new("builder", Span::mixed_site());
+    let args = Ident::new("args", Span::mixed_site());
+    match *data {
+        Data::Struct(ref data) => {
+            match data.fields {
+                Fields::Named(ref fields) => {
+                    let mut flags = Vec::new();
+                    for (f, options) in fields.named.iter().zip(field_options) {
+                        let name = &f.ident;
+                        let flag = options.each.clone().unwrap_or_else(|| name.as_ref().unwrap().to_string());
+                        let flag = format!("--{}", flag.replace('_', "-"));
+                        let invalid = quote! {
+                            return Err(format!("invalid value `{}` for `{}`: {}", value, #flag, err).into());
+                        };
+                        let ty = match get_inner_type(f, "Option") {
+                            Some(GenericArgument::Type(ty)) => ty,
+                            _ => &f.ty,
+                        };
+                        // Values are stored directly rather than through the
+                        // setters, which may be custom, but transforms apply.
+                        let store = match (&options.each, get_vec_item(ty), &options.setter) {
+                            (Some(_), Some(item_ty), setter) => {
+                                let item = from_str(setter, item_ty, quote!(value), &invalid, "`builder(cli)`")?;
+                                quote! { #builder.#name.get_or_insert_with(Vec::new).push(#item); }
+                            }
+                            // A single flag value cannot be parsed into a `Vec`.
+                            (None, Some(_), Setter::Generated | Setter::Custom) => {
+                                return Err(syn::Error::new_spanned(f, "`builder(cli)` cannot fill a `Vec` field from one flag; add `#[builder(each = \"...\")]` to accept the flag repeatedly"));
+                            }
+                            (_, _, setter) => {
+                                let value = from_str(setter, ty, quote!(value), &invalid, "`builder(cli)`")?;
+                                quote! { #builder.#name = Some(#value); }
+                            }
+                        };
+                        flags.push(quote_spanned! {f.span()=>
+                            #flag => {
+                                let value = match #args.next() {
+                                    Some(value) => value,
+                                    None => return Err(format!("missing value for `{}`", #flag).into()),
+                                };
+                                #store
+                            }
+                        });
+                    }
+                    Ok(quote! {
+                        pub fn from_args(#args: impl Iterator<Item = String>) -> Result<Self, Box<dyn ::std::error::Error>> {
+                            let mut #args = #args;
+                            let mut #builder = #name::builder();
+                            while let Some(flag) = #args.next() {
+                                match flag.as_str() {
+                                    #(#flags)*
+                                    _ => return Err(format!("unknown flag `{}`", flag).into()),
+                                }
+                            }
+                            Ok(#builder)
+                        }
+                    })
+                }
+                _ => Err(syn::Error::new_spanned(name, "`builder(cli)` requires a struct with named fields")),
+            }
+        }
+        _ => Err(syn::Error::new_spanned(name, "`builder(cli)` requires a struct with named fields")),
+    }
+}
+
+fn get_with_env_method(data: &Data, name: &Ident, options: &ContainerOptions, field_options: &[FieldOptions]) -> syn::Result<TokenStream2> {
+    let separator = options.env_separator.as_deref().unwrap_or(",");
+    match *data {
+        Data::Struct(ref data) => {
+            match data.fields {
+                Fields::Named(ref fields) => {
+                    let mut vars = Vec::new();
+                    for (f, field_options) in fields.named.iter().zip(field_options) {
+                        let name = &f.ident;
+                        let var = match (&field_options.env, &options.env_prefix) {
+                            (Some(var), _) => var.clone(),
+                            (None, Some(prefix)) => format!("{}{}", prefix, name.as_ref().unwrap().to_string().to_uppercase()),
+                            (None, None) => continue,
+                        };
+                        let invalid = |value| quote! {
+                            return Err(format!("invalid value `{}` in `{}`: {}", #value, #var, err).into());
+                        };
+                        let ty = match get_inner_type(f, "Option") {
+                            Some(GenericArgument::Type(ty)) => ty,
+                            _ => &f.ty,
+                        };
+                        // A `Vec` is given as a list of items, unless a
+                        // transform of the whole field takes the string as is.
+                        // The transform of an `each` setter applies per item.
+                        let value = match (&field_options.each, get_vec_item(ty), &field_options.setter) {
+                            (None, Some(_), setter @ Setter::Transform(_)) | (_, None, setter) => {
+                                from_str(setter, ty, quote!(value), &invalid(quote!(value)), "`with_env`")?
+                            }
+                            (each, Some(item_ty), setter) => {
+                                let setter = if each.is_some() { setter } else { &Setter::Generated };
+                                let item = from_str(setter, item_ty, quote!(item), &invalid(quote!(item)), "`with_env`")?;
+                                quote! {{
+                                    let mut items = Vec::new();
+                                    for item in value.split(#separator).filter(|item| !item.is_empty()) {
+                                        items.push(#item);
+                                    }
+                                    items
+                                }}
+                            }
+                        };
+                        vars.push(quote_spanned! {f.span()=>
+                            if self.#name.is_none() {
+                                match ::std::env::var(#var) {
+                                    Ok(value) => {
+                                        self.#name = Some(#value);
+                                    }
+                                    Err(::std::env::VarError::NotPresent) => {}
+                                    Err(err) => {
+                                        return Err(format!("invalid value in `{}`: {}", #var, err).into());
+                                    }
+                                }
+                            }
+                        });
+                    }
+                    Ok(quote! {
+                        pub fn with_env(&mut self) -> Result<&mut Self, Box<dyn ::std::error::Error>> {
+                            #(#vars)*
+                            Ok(self)
+                        }
+                    })
+                }
+                _ => Err(syn::Error::new_spanned(name, "`with_env` requires a struct with named fields")),
+            }
+        }
+        _ => Err(syn::Error::new_spanned(name, "`with_env` requires a struct with named fields")),
+    }
+}
+
 fn get_builder_definition(data: &Data) -> TokenStream2 {
     match *data {
         Data::Struct(ref data) => {
@@ -229,7 +558,7 @@ fn get_builder_definition(data: &Data) -> TokenStream2 {
                     let defn = fields.named.iter().map(|f| {
                         let name = &f.ident;
                         let ty = &f.ty;
-                        if get_inner_type(&f, "Option").is_none() {
+                        if get_inner_type(f, "Option").is_none() {
                             quote! { #name: Option<#ty> }
                         } else {
                             quote! { #name: #ty }
@@ -274,12 +603,67 @@ fn get_empty_builder(data: &Data) -> TokenStream2 {
 pub fn derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
+    let options = match get_container_options(&input.attrs) {
+        Ok(options) => options,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let field_options = match &input.data {
+        Data::Struct(data) => data.fields.iter().map(parse_field_options).collect(),
+        _ => Ok(Vec::new()),
+    };
+    let field_options: Vec<FieldOptions> = match field_options {
+        Ok(field_options) => field_options,
+        Err(err) => return err.to_compile_error().into(),
+    };
     let empty_builder = get_empty_builder(&input.data);
     let builder_defn = get_builder_definition(&input.data);
-    let builder_impl = get_builder_impl(&input.data).unwrap();
-    let build_method = get_build_method(&input.data, &name);
+    let builder_impl = get_builder_impl(&input.data, &field_options).unwrap();
+    let build_method = get_build_method(&input.data, &name, &options, &field_options);
+    let from_args = if options.cli {
+        match get_from_args_method(&input.data, &name, &field_options) {
+            Ok(from_args) => from_args,
+            Err(err) => return err.to_compile_error().into(),
+        }
+    } else {
+        quote! {}
+    };
+    let uses_env = field_options.iter().any(|options| options.env.is_some());
+    let with_env = if options.env_prefix.is_some() || uses_env {
+        match get_with_env_method(&input.data, &name, &options, &field_options) {
+            Ok(with_env) => with_env,
+            Err(err) => return err.to_compile_error().into(),
+        }
+    } else {
+        quote! {}
+    };
     let builder = format!("{}Builder", name);
     let builder_name = Ident::new(&builder, Span::call_site());
+    // The base instance comes from a trait scoped to an anonymous const, so
+    // that no method is added to the user's type.
+    let build_impl = match &options.default_from {
+        Some(expr) => quote! {
+            const _: () = {
+                trait BuilderDefaultFrom {
+                    fn default_from() -> Self;
+                }
+
+                impl BuilderDefaultFrom for #name {
+                    fn default_from() -> Self {
+                        #expr
+                    }
+                }
+
+                impl #builder_name {
+                    #build_method
+                }
+            };
+        },
+        None => quote! {
+            impl #builder_name {
+                #build_method
+            }
+        },
+    };
     let tokens = quote! {
         impl #name {
             pub fn builder() -> #builder_name {
@@ -295,8 +679,11 @@ pub fn derive(input: TokenStream) -> TokenStream {
         
         impl #builder_name {
             #builder_impl
-            #build_method
+            #from_args
+            #with_env
         }
+
+        #build_impl
     };
     TokenStream::from(tokens)
 }
@@ -0,0 +1,61 @@
+// A struct-level #[builder(default_from = "...")] names an expression that
+// produces a complete instance of the struct. Any field the caller leaves
+// unset is taken from that instance when build() is called, instead of
+// build() returning an error.
+//
+// Inside the expression, `Self` refers to the struct being built, not the
+// builder. The derive adds no method to the struct other than builder(), so
+// the struct's own associated functions may use any name.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(default_from = "Self::baseline()")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+impl Command {
+    fn baseline() -> Self {
+        Command {
+            executable: "sh".to_owned(),
+            args: vec!["-c".to_owned()],
+            env: vec!["PATH=/bin".to_owned()],
+            current_dir: Some("/".to_owned()),
+        }
+    }
+
+    fn builder_default_from() -> Self {
+        Command {
+            executable: "true".to_owned(),
+            args: Vec::new(),
+            env: Vec::new(),
+            current_dir: None,
+        }
+    }
+}
+
+fn main() {
+    let command = Command::builder().build().unwrap();
+    assert_eq!(command.executable, "sh");
+    assert_eq!(command.args, vec!["-c"]);
+    assert_eq!(command.env, vec!["PATH=/bin"]);
+    assert_eq!(command.current_dir, Some("/".to_owned()));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.env, vec!["PATH=/bin"]);
+    assert_eq!(command.current_dir, Some("..".to_owned()));
+
+    assert_eq!(Command::builder_default_from().executable, "true");
+}
@@ -0,0 +1,77 @@
+// With the `cli` feature enabled, #[builder(cli)] generates a
+// `from_args` constructor on the builder that maps `--field value` flags onto
+// the setters. Values are parsed with FromStr, fields with an `each` setter
+// accept the flag repeatedly, and underscores in names become dashes.
+//
+// A setter(transform = ...) closure taking a single parameter is applied to
+// the flag's value, which is passed as is to a `&str` parameter and parsed
+// with FromStr for any other type.
+//
+// Unknown flags, flags without a value and unparseable values are reported
+// through the same error type as build().
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(cli)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    jobs: u32,
+    current_dir: Option<String>,
+    #[builder(setter(transform = |level: &str| level.to_uppercase()))]
+    log_level: String,
+    #[builder(each = "port", setter(transform = |port: u16| format!(":{}", port)))]
+    listen: Vec<String>,
+}
+
+fn args(args: &[&str]) -> impl Iterator<Item = String> {
+    args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>().into_iter()
+}
+
+fn main() {
+    let command = CommandBuilder::from_args(args(&[
+        "--executable", "cargo",
+        "--arg", "build",
+        "--arg", "--release",
+        "--jobs", "4",
+        "--current-dir", "..",
+        "--log-level", "debug",
+        "--port", "80",
+        "--port", "8080",
+    ]))
+    .unwrap()
+    .build()
+    .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.jobs, 4);
+    assert_eq!(command.current_dir, Some("..".to_owned()));
+    assert_eq!(command.log_level, "DEBUG");
+    assert_eq!(command.listen, vec![":80", ":8080"]);
+
+    let missing = CommandBuilder::from_args(args(&["--executable", "cargo"]))
+        .unwrap()
+        .build();
+    assert!(missing.is_err());
+
+    let err = CommandBuilder::from_args(args(&["--verbose"])).err().unwrap();
+    assert_eq!(err.to_string(), "unknown flag `--verbose`");
+
+    let err = CommandBuilder::from_args(args(&["--jobs"])).err().unwrap();
+    assert_eq!(err.to_string(), "missing value for `--jobs`");
+
+    let err = CommandBuilder::from_args(args(&["--jobs", "many"])).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "invalid value `many` for `--jobs`: invalid digit found in string",
+    );
+
+    let err = CommandBuilder::from_args(args(&["--port", "http"])).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "invalid value `http` for `--port`: invalid digit found in string",
+    );
+}
@@ -0,0 +1,61 @@
+// A struct-level #[builder(env_prefix = "...")] generates a `with_env` method
+// on the builder that fills every field still unset from an environment
+// variable named by the prefix followed by the field name in upper case. A
+// field-level #[builder(env = "...")] names the variable explicitly, and Vec
+// fields, with or without an `each` setter, are split on
+// #[builder(env_separator = "...")], or on commas by default.
+//
+// Values are parsed with FromStr. Fields already given through a setter are
+// left alone. A setter(transform = ...) closure taking a single parameter is
+// applied to the value, or to each item of an `each` field, like it is by
+// from_args.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(env_prefix = "APP_", env_separator = ":")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    features: Vec<String>,
+    #[builder(env = "APP_JOB_COUNT")]
+    jobs: u32,
+    current_dir: Option<String>,
+    #[builder(setter(transform = |level: &str| level.to_uppercase()))]
+    log_level: String,
+    #[builder(each = "port", setter(transform = |port: u16| format!(":{}", port)))]
+    listen: Vec<String>,
+}
+
+fn main() {
+    std::env::set_var("APP_EXECUTABLE", "rustc");
+    std::env::set_var("APP_ARGS", "--edition:2021");
+    std::env::set_var("APP_FEATURES", "cli:env");
+    std::env::set_var("APP_JOB_COUNT", "8");
+    std::env::remove_var("APP_CURRENT_DIR");
+    std::env::set_var("APP_LOG_LEVEL", "debug");
+    std::env::set_var("APP_LISTEN", "80:8080");
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .with_env()
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["--edition", "2021"]);
+    assert_eq!(command.features, vec!["cli", "env"]);
+    assert_eq!(command.jobs, 8);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.log_level, "DEBUG");
+    assert_eq!(command.listen, vec![":80", ":8080"]);
+
+    std::env::set_var("APP_JOB_COUNT", "eight");
+    let err = Command::builder().with_env().err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "invalid value `eight` in `APP_JOB_COUNT`: invalid digit found in string",
+    );
+}
@@ -0,0 +1,47 @@
+// #[builder(setter(custom))] suppresses the generated setter for a field so
+// that the caller can write their own in an `impl` block on the builder. The
+// field keeps its storage in the builder and is still checked by build().
+//
+// #[builder(setter(transform = |...| ...))] generates a setter whose
+// parameters are the closure's parameters and which stores the value the
+// closure's body evaluates to. Closure parameters must have a type.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(transform = |name: &str| name.to_uppercase()))]
+    executable: String,
+    #[builder(each = "arg", setter(transform = |key: &str, value: u32| format!("--{}={}", key, value)))]
+    args: Vec<String>,
+    #[builder(setter(custom))]
+    env: Vec<String>,
+    #[builder(setter(transform = |dir: &str| dir.to_owned()))]
+    current_dir: Option<String>,
+}
+
+impl CommandBuilder {
+    fn env(&mut self, key: &str, value: &str) -> &mut Self {
+        self.env
+            .get_or_insert_with(Vec::new)
+            .push(format!("{}={}", key, value));
+        self
+    }
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("jobs", 4)
+        .env("RUST_LOG", "info")
+        .current_dir("..")
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "CARGO");
+    assert_eq!(command.args, vec!["--jobs=4"]);
+    assert_eq!(command.env, vec!["RUST_LOG=info"]);
+    assert_eq!(command.current_dir, Some("..".to_owned()));
+
+    assert!(Command::builder().executable("cargo").build().is_err());
+}
@@ -0,0 +1,14 @@
+// A one-at-a-time `each` setter pushes onto the stored Vec, so using the
+// attribute on a field of any other type is reported at the attribute rather
+// than as a type error inside the generated setter.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: String,
+}
+
+fn main() {}
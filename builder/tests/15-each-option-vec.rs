@@ -0,0 +1,14 @@
+// An `each` field is already optional: a builder that was never given an item
+// produces an empty Vec. Wrapping the Vec in an Option is rejected with a
+// suggestion to drop the Option.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Option<Vec<String>>,
+}
+
+fn main() {}
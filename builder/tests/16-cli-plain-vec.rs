@@ -0,0 +1,14 @@
+// A flag given to from_args carries a single value, which cannot be parsed into
+// a whole Vec. A Vec field without an `each` setter is rejected under
+// #[builder(cli)], pointing at the field and suggesting `each`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(cli)]
+pub struct Command {
+    executable: String,
+    args: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// A transform taking several parameters cannot be fed from the single string
+// of an environment variable or a flag, so with_env and from_args reject it at
+// the closure's parameters. This is a compile_fail test.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(env_prefix = "APP_")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg", setter(transform = |key: &str, value: u32| format!("--{}={}", key, value)))]
+    args: Vec<String>,
+}
+
+fn main() {}
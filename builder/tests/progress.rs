@@ -10,6 +10,18 @@ fn tests() {
     t.pass("tests/05-method-chaining.rs");
     t.pass("tests/06-optional-field.rs");
     t.pass("tests/07-repeated-field.rs");
-    //t.compile_fail("tests/08-unrecognized-attribute.rs");
+    t.compile_fail("tests/08-unrecognized-attribute.rs");
     //t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-default-from.rs");
+    if cfg!(feature = "cli") {
+        t.pass("tests/11-from-args.rs");
+    }
+    t.pass("tests/12-with-env.rs");
+    t.pass("tests/13-setter-options.rs");
+    t.compile_fail("tests/14-each-not-vec.rs");
+    t.compile_fail("tests/15-each-option-vec.rs");
+    if cfg!(feature = "cli") {
+        t.compile_fail("tests/16-cli-plain-vec.rs");
+    }
+    t.compile_fail("tests/17-env-transform-params.rs");
 }
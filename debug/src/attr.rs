@@ -0,0 +1,175 @@
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Field, Ident, Lit, LitStr, Meta, NestedMeta, Path, Token, WherePredicate};
+
+/// Placeholder printed in place of a redacted field.
+const REDACTED: &str = "***";
+
+/// Options given on the type through `#[debug(...)]`.
+#[derive(Default)]
+pub struct ContainerOptions {
+    /// Where-predicates of `debug(bound = "...")`, replacing all inferred
+    /// bounds.
+    pub bound: Option<Vec<WherePredicate>>,
+    /// Fields left readable by `debug(redact_all_except(...))`; every other
+    /// field is redacted.
+    pub redact_all_except: Option<Vec<Ident>>,
+    /// Print on a single line even in alternate (`{:#?}`) mode.
+    pub compact: bool,
+    /// Number of items of a collection field printed before the rest are
+    /// summarized as `... (N more)`.
+    pub max_items: Option<usize>,
+    /// Print integer fields as `{:#x}`.
+    pub hex: bool,
+    /// Function of type `fn(&Self) -> &str` naming the active field of a
+    /// union.
+    pub tag: Option<Path>,
+}
+
+impl ContainerOptions {
+    /// Whether the container redacts the field regardless of its own options.
+    fn redacts(&self, f: &Field) -> bool {
+        match &self.redact_all_except {
+            Some(except) => !f.ident.as_ref().is_some_and(|ident| except.contains(ident)),
+            None => false,
+        }
+    }
+}
+
+/// Options given on a field through `#[debug = "..."]` and `#[debug(...)]`.
+#[derive(Default)]
+pub struct FieldOptions {
+    /// Format string applied to the field's value.
+    pub format: Option<LitStr>,
+    /// Leave the field out, marking the output as non-exhaustive.
+    pub skip: bool,
+    /// Placeholder printed instead of the field's value.
+    pub redact: Option<String>,
+    /// Function formatting the field, of type
+    /// `fn(&T, &mut fmt::Formatter) -> fmt::Result`.
+    pub with: Option<Path>,
+    /// Where-predicates replacing the bounds inferred from this field's type.
+    pub bound: Option<Vec<WherePredicate>>,
+}
+
+pub fn container_options(attrs: &[Attribute]) -> syn::Result<ContainerOptions> {
+    let mut options = ContainerOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("debug")) {
+        let meta = match attr.parse_meta()? {
+            Meta::List(meta) => meta,
+            meta => return Err(syn::Error::new_spanned(meta, "expected `debug(...)`")),
+        };
+        for nested in meta.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("bound") => {
+                    let predicates = parse_predicates(&meta.lit)?;
+                    options.bound.get_or_insert_with(Vec::new).extend(predicates);
+                }
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("redact_all_except") => {
+                    let except = options.redact_all_except.get_or_insert_with(Vec::new);
+                    for nested in list.nested {
+                        match nested {
+                            NestedMeta::Meta(Meta::Path(path)) if path.get_ident().is_some() => {
+                                except.push(path.get_ident().unwrap().clone());
+                            }
+                            nested => return Err(syn::Error::new_spanned(nested, "expected a field name")),
+                        }
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("tag") => match &meta.lit {
+                    Lit::Str(path) => options.tag = Some(path.parse()?),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected a path to a function")),
+                },
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("compact") => options.compact = true,
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("hex") => options.hex = true,
+                NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("max_items") => {
+                    match &meta.lit {
+                        Lit::Int(max) => options.max_items = Some(max.base10_parse()?),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected an integer")),
+                    }
+                }
+                nested => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "expected one of `bound`, `redact_all_except`, `compact`, `max_items`, `hex` or `tag`",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(options)
+}
+
+/// The options of a field, including a redaction imposed by the container.
+pub fn field_options(f: &Field, container: &ContainerOptions) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in f.attrs.iter().filter(|attr| attr.path.is_ident("debug")) {
+        match attr.parse_meta()? {
+            Meta::NameValue(meta) => match meta.lit {
+                Lit::Str(format) => options.format = Some(format),
+                lit => return Err(syn::Error::new_spanned(lit, "expected a format string")),
+            },
+            Meta::List(list) => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => options.skip = true,
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("redact") => {
+                            options.redact = Some(REDACTED.to_owned());
+                        }
+                        NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("redact") => {
+                            match meta.lit {
+                                Lit::Str(placeholder) => options.redact = Some(placeholder.value()),
+                                lit => return Err(syn::Error::new_spanned(lit, "expected a placeholder string")),
+                            }
+                        }
+                        NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("bound") => {
+                            let predicates = parse_predicates(&meta.lit)?;
+                            options.bound.get_or_insert_with(Vec::new).extend(predicates);
+                        }
+                        NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("with") => {
+                            match meta.lit {
+                                Lit::Str(path) => options.with = Some(path.parse()?),
+                                lit => return Err(syn::Error::new_spanned(lit, "expected a path to a function")),
+                            }
+                        }
+                        nested => {
+                            return Err(syn::Error::new_spanned(
+                                nested,
+                                "expected one of `skip`, `redact`, `with` or `bound`",
+                            ))
+                        }
+                    }
+                }
+            }
+            meta => return Err(syn::Error::new_spanned(meta, "expected `debug = \"...\"`")),
+        }
+    }
+    if options.redact.is_none() && container.redacts(f) {
+        options.redact = Some(REDACTED.to_owned());
+    }
+    Ok(options)
+}
+
+/// The format string of a `#[display("...")]` attribute, if any.
+pub fn display_format(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    let attr = match attrs.iter().find(|attr| attr.path.is_ident("display")) {
+        Some(attr) => attr,
+        None => return Ok(None),
+    };
+    match attr.parse_meta()? {
+        Meta::List(list) if list.nested.len() == 1 => match &list.nested[0] {
+            NestedMeta::Lit(Lit::Str(format)) => Ok(Some(format.clone())),
+            nested => Err(syn::Error::new_spanned(nested, "expected a format string")),
+        },
+        meta => Err(syn::Error::new_spanned(meta, "expected `display(\"...\")`")),
+    }
+}
+
+/// Parses the string of a `bound = "..."` option, which may be empty.
+fn parse_predicates(lit: &Lit) -> syn::Result<Vec<WherePredicate>> {
+    let predicates = match lit {
+        Lit::Str(predicates) => predicates,
+        lit => return Err(syn::Error::new_spanned(lit, "expected a string of where-predicates")),
+    };
+    let parser = Punctuated::<WherePredicate, Token![,]>::parse_terminated;
+    Ok(predicates.parse_with(parser)?.into_iter().collect())
+}
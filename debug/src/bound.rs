@@ -0,0 +1,153 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use std::collections::BTreeSet;
+use syn::visit::{self, Visit};
+use syn::{
+    parse_quote, Field, Generics, Ident, Type, TypeBareFn, TypeImplTrait, TypePath, TypeTraitObject,
+    WherePredicate,
+};
+
+/// Adds the bounds needed by the generated impl, given each formatted field
+/// along with the formatting trait its value must implement: `T: Trait` for
+/// every type parameter used directly in a field, and `T::Assoc: Trait` or
+/// `<T as Trait>::Assoc: Trait` for every associated type of a type
+/// parameter. `PhantomData<T>` and fn pointers are `Debug` whatever their
+/// parameters, and a field containing a trait object over a type parameter is
+/// bounded as a whole.
+///
+/// Each field may replace the bounds inferred from its type, and an explicit
+/// container `bound` replaces all inferred bounds. Duplicate predicates are
+/// emitted once.
+pub fn add_bounds<'a>(
+    generics: &Generics,
+    fields: impl Iterator<Item = (&'a Field, TokenStream, Option<&'a [WherePredicate]>)>,
+    bound: Option<&[WherePredicate]>,
+) -> Generics {
+    let mut generics = generics.clone();
+    let mut predicates = Vec::new();
+    match bound {
+        Some(bound) => predicates.extend(bound.iter().cloned()),
+        None => {
+            let params = generics
+                .type_params()
+                .map(|param| param.ident.clone())
+                .collect();
+            for (f, fmt_trait, bound) in fields {
+                match bound {
+                    Some(bound) => predicates.extend(bound.iter().cloned()),
+                    None => predicates.extend(infer_bounds(&params, f, &fmt_trait)),
+                }
+            }
+        }
+    }
+
+    let mut seen = BTreeSet::new();
+    let where_clause = generics.make_where_clause();
+    for predicate in predicates {
+        if seen.insert(predicate.to_token_stream().to_string()) {
+            where_clause.predicates.push(predicate);
+        }
+    }
+    generics
+}
+
+fn infer_bounds(params: &BTreeSet<Ident>, f: &Field, fmt_trait: &TokenStream) -> Vec<WherePredicate> {
+    let mut collector = BoundCollector {
+        params,
+        bounded: BTreeSet::new(),
+        associated: Vec::new(),
+        opaque: false,
+    };
+    collector.visit_type(&f.ty);
+    let ty = &f.ty;
+    if collector.opaque {
+        return vec![parse_quote!(#ty: #fmt_trait)];
+    }
+    let mut predicates: Vec<WherePredicate> = collector
+        .bounded
+        .iter()
+        .map(|param| parse_quote!(#param: #fmt_trait))
+        .collect();
+    for path in collector.associated {
+        predicates.push(parse_quote!(#path: #fmt_trait));
+    }
+    predicates
+}
+
+struct BoundCollector<'a> {
+    params: &'a BTreeSet<Ident>,
+    /// Type parameters appearing directly in a field type.
+    bounded: BTreeSet<Ident>,
+    /// Paths such as `T::Value` rooted at a type parameter.
+    associated: Vec<TypePath>,
+    /// Whether a trait object or `impl Trait` mentions a type parameter, in
+    /// which case no bound on the parameters alone can make the field `Debug`.
+    opaque: bool,
+}
+
+impl BoundCollector<'_> {
+    fn is_param(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Path(ty) => {
+                ty.qself.is_none()
+                    && ty.path.get_ident().is_some_and(|ident| self.params.contains(ident))
+            }
+            _ => false,
+        }
+    }
+
+    fn mentions_params(&self, visit: impl FnOnce(&mut BoundCollector<'_>)) -> bool {
+        let mut inner = BoundCollector {
+            params: self.params,
+            bounded: BTreeSet::new(),
+            associated: Vec::new(),
+            opaque: false,
+        };
+        visit(&mut inner);
+        !inner.bounded.is_empty() || !inner.associated.is_empty() || inner.opaque
+    }
+}
+
+impl<'ast> Visit<'ast> for BoundCollector<'_> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        let last = ty.path.segments.last().unwrap();
+        if last.ident == "PhantomData" {
+            return;
+        }
+        match &ty.qself {
+            Some(qself) if self.is_param(&qself.ty) => {
+                if !self.associated.contains(ty) {
+                    self.associated.push(ty.clone());
+                }
+                return;
+            }
+            Some(_) => {}
+            None => {
+                let first = &ty.path.segments[0].ident;
+                if self.params.contains(first) {
+                    if ty.path.segments.len() == 1 {
+                        self.bounded.insert(first.clone());
+                    } else if !self.associated.contains(ty) {
+                        self.associated.push(ty.clone());
+                    }
+                    return;
+                }
+            }
+        }
+        visit::visit_type_path(self, ty);
+    }
+
+    fn visit_type_bare_fn(&mut self, _ty: &'ast TypeBareFn) {}
+
+    fn visit_type_trait_object(&mut self, ty: &'ast TypeTraitObject) {
+        if self.mentions_params(|inner| visit::visit_type_trait_object(inner, ty)) {
+            self.opaque = true;
+        }
+    }
+
+    fn visit_type_impl_trait(&mut self, ty: &'ast TypeImplTrait) {
+        if self.mentions_params(|inner| visit::visit_type_impl_trait(inner, ty)) {
+            self.opaque = true;
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{attr, binding, bound, format, pattern};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields, Ident, LitStr};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let missing = |ident: &Ident| syn::Error::new(ident.span(), "missing `#[display(\"...\")]`");
+    let mut formatted = Vec::new();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let format = attr::display_format(&input.attrs)?.ok_or_else(|| missing(name))?;
+            let (pattern, display) = display_fields(quote!(Self), &data.fields, &format, &mut formatted)?;
+            quote! {
+                match self {
+                    #pattern => #display,
+                }
+            }
+        }
+        Data::Enum(data) if data.variants.is_empty() => quote!(match *self {}),
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    let format = attr::display_format(&variant.attrs)?.ok_or_else(|| missing(ident))?;
+                    let (pattern, display) =
+                        display_fields(quote!(Self::#ident), &variant.fields, &format, &mut formatted)?;
+                    Ok(quote!(#pattern => #display,))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(name.span(), "CustomDisplay does not support unions"));
+        }
+    };
+
+    let generics = bound::add_bounds(
+        &input.generics,
+        formatted.into_iter().map(|(f, fmt_trait)| (f, fmt_trait, None)),
+        None,
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::std::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+/// A pattern binding the fields of a struct or variant that `format` refers
+/// to, and the expression writing the format to `f`. Every formatted field is
+/// recorded in `formatted` together with the trait its format spec requires.
+fn display_fields<'a>(
+    path: TokenStream2,
+    fields: &'a Fields,
+    format: &LitStr,
+    formatted: &mut Vec<(&'a Field, TokenStream2)>,
+) -> syn::Result<(TokenStream2, TokenStream2)> {
+    let all_fields = fields;
+    let fields: Vec<&Field> = fields.iter().collect();
+    let bindings: Vec<Ident> = (0..fields.len()).map(binding).collect();
+    let mut used = vec![false; fields.len()];
+    let rewritten = format::map_arguments(&format.value(), |argument, spec| {
+        if argument.is_empty() {
+            return Err("name the field to format, as in `{name}` or `{0}`".to_owned());
+        }
+        let index = fields
+            .iter()
+            .enumerate()
+            .position(|(i, f)| match &f.ident {
+                Some(ident) => ident == argument,
+                None => argument == i.to_string(),
+            })
+            .ok_or_else(|| format!("no field `{}`", argument))?;
+        used[index] = true;
+        formatted.push((fields[index], format_trait(spec)));
+        Ok(bindings[index].to_string())
+    });
+    let rewritten = LitStr::new(
+        &rewritten.map_err(|msg| syn::Error::new(format.span(), msg))?,
+        format.span(),
+    );
+
+    let named: Vec<&Ident> = bindings
+        .iter()
+        .zip(&used)
+        .filter(|(_, used)| **used)
+        .map(|(binding, _)| binding)
+        .collect();
+    let bindings: Vec<TokenStream2> = bindings
+        .iter()
+        .zip(used)
+        .map(|(binding, used)| if used { quote!(#binding) } else { quote!(_) })
+        .collect();
+    Ok((
+        pattern(path, all_fields, &bindings),
+        quote!(::std::write!(f, #rewritten, #(#named = #named),*)),
+    ))
+}
+
+/// The formatting trait required by a format spec such as `>8` or `#x`.
+fn format_trait(spec: &str) -> TokenStream2 {
+    if spec.ends_with('?') {
+        return quote!(::std::fmt::Debug);
+    }
+    match spec.chars().last() {
+        Some('x') => quote!(::std::fmt::LowerHex),
+        Some('X') => quote!(::std::fmt::UpperHex),
+        Some('o') => quote!(::std::fmt::Octal),
+        Some('b') => quote!(::std::fmt::Binary),
+        Some('e') => quote!(::std::fmt::LowerExp),
+        Some('E') => quote!(::std::fmt::UpperExp),
+        _ => quote!(::std::fmt::Display),
+    }
+}
@@ -0,0 +1,46 @@
+/// Rewrites the argument of every `{...}` placeholder in a format string,
+/// keeping any format spec after the `:` and any escaped braces as they are.
+///
+/// The callback receives the argument as written (empty for `{}`) and the
+/// format spec (empty if there is none), and returns the argument's
+/// replacement or a message describing why the argument is invalid.
+pub fn map_arguments(
+    format: &str,
+    mut f: impl FnMut(&str, &str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(format.len());
+    let mut chars = format.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push_str("{{");
+            }
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => inner.push(ch),
+                        None => return Err("unterminated `{` in format string".to_owned()),
+                    }
+                }
+                let (argument, spec) = match inner.find(':') {
+                    Some(colon) => inner.split_at(colon),
+                    None => (inner.as_str(), ""),
+                };
+                out.push('{');
+                out.push_str(&f(argument.trim(), spec.strip_prefix(':').unwrap_or(""))?);
+                out.push_str(spec);
+                out.push('}');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push_str("}}");
+            }
+            '}' => return Err("unmatched `}` in format string".to_owned()),
+            ch => out.push(ch),
+        }
+    }
+    Ok(out)
+}
@@ -1,8 +1,497 @@
+mod attr;
+mod bound;
+mod display;
+mod format;
+
+use crate::attr::ContainerOptions;
 use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Field, Fields, Ident, LitStr, Meta, NestedMeta,
+    Type,
+};
 
-#[proc_macro_derive(CustomDebug)]
+#[proc_macro_derive(CustomDebug, attributes(debug))]
 pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input, Output::Debug)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Generates an inherent `inspect` method passing the name and value of each
+/// field, as printed by CustomDebug, to a visitor:
+///
+/// ```ignore
+/// pub fn inspect(&self, visitor: &mut dyn FnMut(&str, &dyn Debug))
+/// ```
+///
+/// Tuple fields are named by their index. For an enum, the fields of the
+/// active variant are visited.
+#[proc_macro_derive(Inspect, attributes(debug))]
+pub fn derive_inspect(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input, Output::Inspect)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(CustomDisplay, attributes(display))]
+pub fn derive_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    display::expand(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// What is done with the fields: CustomDebug writes them to the formatter `f`,
+/// Inspect passes them to the callback `visitor`.
+#[derive(Clone, Copy)]
+enum Output {
+    Debug,
+    Inspect,
+}
+
+fn expand(input: DeriveInput, output: Output) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let options = attr::container_options(&input.attrs)?;
+    let body = match &input.data {
+        Data::Struct(data) if is_packed(&input.attrs) => {
+            // References to fields of a packed struct may be unaligned, so
+            // the fields are copied out and formatted from the copies.
+            let (pattern, debug, bound) = debug_fields(quote!(Self), name, &data.fields, &options, output)?;
+            let fields: Vec<&Field> = data.fields.iter().collect();
+            let copies = bound.iter().map(|&i| {
+                let binding = binding(i);
+                let ty = &fields[i].ty;
+                quote_spanned! {ty.span()=>
+                    assert_copy::<#ty>();
+                    let #binding = &#binding;
+                }
+            });
+            quote! {
+                fn assert_copy<T: ::std::marker::Copy>() {}
+                match *self {
+                    #pattern => {
+                        #(#copies)*
+                        #debug
+                    }
+                }
+            }
+        }
+        Data::Struct(data) => {
+            let (pattern, debug, _) = debug_fields(quote!(Self), name, &data.fields, &options, output)?;
+            quote! {
+                match self {
+                    #pattern => #debug,
+                }
+            }
+        }
+        Data::Enum(data) if data.variants.is_empty() => quote!(match *self {}),
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    let (pattern, debug, _) = debug_fields(quote!(Self::#ident), ident, &variant.fields, &options, output)?;
+                    Ok(quote!(#pattern => #debug,))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(data) => {
+            let label = name.to_string();
+            // Without a tag nothing says which field is active, and reading
+            // any field could observe uninitialized bytes, so the union is
+            // shown as opaque.
+            let raw = match output {
+                Output::Debug => quote!(f.debug_struct(#label).finish_non_exhaustive()),
+                Output::Inspect => quote!({}),
+            };
+            match &options.tag {
+                None => raw,
+                Some(tag) => {
+                    let arms = data
+                        .fields
+                        .named
+                        .iter()
+                        .map(|f| {
+                            let ident = f.ident.as_ref().unwrap();
+                            let active = ident.to_string();
+                            let mut single = data.fields.clone();
+                            single.named = std::iter::once(f.clone()).collect();
+                            let (pattern, debug, _) = debug_fields(quote!(Self), name, &Fields::Named(single), &options, output)?;
+                            // The tag function vouches for the active field.
+                            Ok(quote! {
+                                #active => unsafe {
+                                    match self {
+                                        #pattern => #debug,
+                                    }
+                                },
+                            })
+                        })
+                        .collect::<syn::Result<Vec<_>>>()?;
+                    quote! {
+                        let tag: fn(&Self) -> &str = #tag;
+                        match tag(self) {
+                            #(#arms)*
+                            _ => { #raw }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    // Only fields whose value is actually printed need to implement Debug.
+    let fields: Vec<&Field> = match &input.data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|variant| &variant.fields).collect(),
+        Data::Union(data) if options.tag.is_some() => data.fields.named.iter().collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    let mut printed = Vec::new();
+    for f in fields {
+        let field_options = attr::field_options(f, &options)?;
+        if !field_options.skip && field_options.redact.is_none() && field_options.with.is_none() {
+            printed.push((f, field_options.bound));
+        }
+    }
+    let generics = bound::add_bounds(
+        &input.generics,
+        printed.iter().map(|(f, bound)| (*f, quote!(::std::fmt::Debug), bound.as_deref())),
+        options.bound.as_deref(),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(match output {
+        Output::Debug => {
+            let compact = if options.compact {
+                quote! {
+                    if f.alternate() {
+                        return ::std::write!(f, "{:?}", self);
+                    }
+                }
+            } else {
+                quote!()
+            };
+            quote! {
+                impl #impl_generics ::std::fmt::Debug for #name #ty_generics #where_clause {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        #compact
+                        #body
+                    }
+                }
+            }
+        }
+        Output::Inspect => quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                pub fn inspect(&self, visitor: &mut dyn ::std::ops::FnMut(&str, &dyn ::std::fmt::Debug)) {
+                    #body
+                }
+            }
+        },
+    })
+}
+
+/// A pattern binding the fields of a struct or variant by reference, the
+/// expression writing them to the formatter `f` the same way as std's
+/// `#[derive(Debug)]` (or passing them to the `visitor` of Inspect), and the
+/// indices of the fields the pattern binds.
+fn debug_fields(
+    path: TokenStream2,
+    ident: &Ident,
+    fields: &Fields,
+    container: &ContainerOptions,
+    output: Output,
+) -> syn::Result<(TokenStream2, TokenStream2, Vec<usize>)> {
+    let label = ident.to_string();
+    if fields.is_empty() {
+        let empty = match output {
+            Output::Debug => quote!(f.write_str(#label)),
+            Output::Inspect => quote!({}),
+        };
+        return Ok((pattern(path, fields, &[]), empty, Vec::new()));
+    }
+
+    let field_options = fields
+        .iter()
+        .map(|f| attr::field_options(f, container))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let bindings: Vec<Ident> = (0..fields.len()).map(binding).collect();
+
+    let mut used = vec![false; fields.len()];
+    let mut entries = Vec::new();
+    let mut non_exhaustive = false;
+    for (i, (f, options)) in fields.iter().zip(&field_options).enumerate() {
+        if options.skip {
+            non_exhaustive = true;
+            continue;
+        }
+        let binding = &bindings[i];
+        let value = if let Some(placeholder) = &options.redact {
+            quote_spanned!(f.span()=> &::std::format_args!("{}", #placeholder))
+        } else if let Some(with) = &options.with {
+            used[i] = true;
+            quote_spanned! {f.span()=>
+                &{
+                    struct DebugWith<'a, T: ?::std::marker::Sized>(
+                        &'a T,
+                        fn(&T, &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result,
+                    );
+                    impl<T: ?::std::marker::Sized> ::std::fmt::Debug for DebugWith<'_, T> {
+                        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                            (self.1)(self.0, f)
+                        }
+                    }
+                    DebugWith(#binding, #with)
+                }
+            }
+        } else if let Some(format) = &options.format {
+            let mut positional = false;
+            let mut implicit = 0;
+            let mut siblings = Vec::new();
+            let rewritten = format::map_arguments(&format.value(), |argument, _spec| {
+                // The field's value is the only positional argument, so it
+                // can be taken by `{}` once and by `{0}` any number of times.
+                if argument.is_empty() {
+                    implicit += 1;
+                    if implicit > 1 {
+                        return Err("the field's value is the only positional argument; \
+                                    use `{0}` to format it more than once"
+                            .to_owned());
+                    }
+                }
+                if let Ok(index) = argument.parse::<usize>() {
+                    if index != 0 {
+                        return Err(format!(
+                            "invalid reference to positional argument {}; \
+                             the field's value is the only one, `{{0}}`",
+                            index,
+                        ));
+                    }
+                }
+                if argument.is_empty() || argument.parse::<usize>().is_ok() {
+                    positional = true;
+                    return Ok(argument.to_owned());
+                }
+                let sibling = match argument.strip_prefix("self.") {
+                    Some(sibling) => sibling,
+                    None => return Ok(argument.to_owned()),
+                };
+                let index = fields
+                    .iter()
+                    .enumerate()
+                    .position(|(j, f)| match &f.ident {
+                        Some(ident) => ident == sibling,
+                        None => sibling == j.to_string(),
+                    })
+                    .ok_or_else(|| format!("no field `{}` on `{}`", sibling, label))?;
+                // Printing a sibling must not reveal what its own options hide.
+                if field_options[index].skip || field_options[index].redact.is_some() {
+                    let hidden = if field_options[index].skip { "skipped" } else { "redacted" };
+                    return Err(format!("field `{}` is {} and cannot be referenced by another field", sibling, hidden));
+                }
+                if !siblings.contains(&index) {
+                    siblings.push(index);
+                }
+                Ok(bindings[index].to_string())
+            });
+            let rewritten = LitStr::new(
+                &rewritten.map_err(|msg| syn::Error::new(format.span(), msg))?,
+                format.span(),
+            );
+            used[i] |= positional;
+            let positional = if positional { quote!(#binding,) } else { quote!() };
+            let named: Vec<&Ident> = siblings.iter().map(|&j| &bindings[j]).collect();
+            for j in siblings {
+                used[j] = true;
+            }
+            quote_spanned!(f.span()=> &::std::format_args!(#rewritten, #positional #(#named = #named),*))
+        } else if let (Some(kind), Some(max)) = (collection_kind(&f.ty), container.max_items) {
+            used[i] = true;
+            truncated(kind, binding, max)
+        } else if container.hex && is_integer(&f.ty) {
+            used[i] = true;
+            quote_spanned!(f.span()=> &::std::format_args!("{:#x}", #binding))
+        } else {
+            used[i] = true;
+            quote_spanned!(f.span()=> #binding)
+        };
+        entries.push(match (output, &f.ident) {
+            (Output::Debug, Some(ident)) => {
+                let label = ident.to_string();
+                quote!(.field(#label, #value))
+            }
+            (Output::Debug, None) => quote!(.field(#value)),
+            (Output::Inspect, ident) => {
+                let label = match ident {
+                    Some(ident) => ident.to_string(),
+                    None => i.to_string(),
+                };
+                quote!(visitor(#label, #value);)
+            }
+        });
+    }
+    let bound: Vec<usize> = (0..fields.len()).filter(|&i| used[i]).collect();
+    let bindings: Vec<TokenStream2> = bindings
+        .iter()
+        .zip(used)
+        .map(|(binding, used)| if used { quote!(#binding) } else { quote!(_) })
+        .collect();
+    let finish = if non_exhaustive {
+        quote!(.finish_non_exhaustive())
+    } else {
+        quote!(.finish())
+    };
+
+    let debug = match (output, fields) {
+        (Output::Debug, Fields::Named(_)) => quote!(f.debug_struct(#label) #(#entries)* #finish),
+        (Output::Debug, _) => quote!(f.debug_tuple(#label) #(#entries)* #finish),
+        (Output::Inspect, _) => quote!({ #(#entries)* }),
+    };
+    Ok((pattern(path, fields, &bindings), debug, bound))
+}
+
+/// The variable bound to the field at `index`. Bindings are hygienic so that
+/// they cannot shadow the formatter.
+fn binding(index: usize) -> Ident {
+    Ident::new(&format!("field_{}", index), Span::mixed_site())
+}
+
+/// Whether the struct is `#[repr(packed)]` or `#[repr(packed(N))]`.
+fn is_packed(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("repr"))
+        .any(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested.iter().any(|nested| match nested {
+                NestedMeta::Meta(meta) => meta.path().is_ident("packed"),
+                NestedMeta::Lit(_) => false,
+            }),
+            _ => false,
+        })
+}
+
+/// A pattern matching the struct or variant at `path`, binding each field to
+/// the corresponding entry of `bindings` (or all fields to nothing if
+/// `bindings` is empty).
+fn pattern(path: TokenStream2, fields: &Fields, bindings: &[TokenStream2]) -> TokenStream2 {
+    match fields {
+        Fields::Named(_) if bindings.is_empty() => quote!(#path { .. }),
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|f| &f.ident);
+            quote!(#path { #(#idents: #bindings),* })
+        }
+        Fields::Unnamed(_) if bindings.is_empty() => quote!(#path(..)),
+        Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+        Fields::Unit => path,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Collection {
+    List,
+    Set,
+    Map,
+}
+
+/// Recognizes the std collections by the last segment of their path, along
+/// with arrays and slices.
+fn collection_kind(ty: &Type) -> Option<Collection> {
+    match ty {
+        Type::Array(_) | Type::Slice(_) => Some(Collection::List),
+        Type::Reference(ty) => collection_kind(&ty.elem),
+        Type::Path(ty) => match ty.path.segments.last()?.ident.to_string().as_str() {
+            "Vec" | "VecDeque" | "LinkedList" | "BinaryHeap" => Some(Collection::List),
+            "HashSet" | "BTreeSet" => Some(Collection::Set),
+            "HashMap" | "BTreeMap" => Some(Collection::Map),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_integer(ty: &Type) -> bool {
+    const INTEGERS: &[&str] = &[
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    match ty {
+        Type::Path(ty) => ty
+            .path
+            .get_ident()
+            .is_some_and(|ident| INTEGERS.iter().any(|int| ident == int)),
+        _ => false,
+    }
+}
 
-    unimplemented!()
+/// A value printing at most `max` items of the collection bound to `binding`
+/// like std's Debug for the collection does, followed by `... (N more)`.
+fn truncated(kind: Collection, binding: &Ident, max: usize) -> TokenStream2 {
+    let (set, iter) = match kind {
+        Collection::List => (false, quote!(#binding.iter())),
+        Collection::Set => (true, quote!(#binding.iter())),
+        Collection::Map => (true, quote!(#binding.iter().map(|(key, value)| MapEntry(key, value)))),
+    };
+    // Map entries are printed as `key: value` items of a set.
+    let map_entry = match kind {
+        Collection::Map => quote! {
+            struct MapEntry<K, V>(K, V);
+            impl<K: ::std::fmt::Debug, V: ::std::fmt::Debug> ::std::fmt::Debug for MapEntry<K, V> {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    self.0.fmt(f)?;
+                    f.write_str(": ")?;
+                    self.1.fmt(f)
+                }
+            }
+        },
+        _ => quote!(),
+    };
+    quote! {
+        &{
+            struct Truncated<I> {
+                iter: I,
+                max: usize,
+                set: bool,
+            }
+            impl<I> ::std::fmt::Debug for Truncated<I>
+            where
+                I: ::std::iter::Iterator + ::std::clone::Clone,
+                I::Item: ::std::fmt::Debug,
+            {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    let more = self.iter.clone().count().saturating_sub(self.max);
+                    let items = self.iter.clone().take(self.max);
+                    if self.set {
+                        let mut set = f.debug_set();
+                        set.entries(items);
+                        if more > 0 {
+                            set.entry(&::std::format_args!("... ({} more)", more));
+                        }
+                        set.finish()
+                    } else {
+                        let mut list = f.debug_list();
+                        list.entries(items);
+                        if more > 0 {
+                            list.entry(&::std::format_args!("... ({} more)", more));
+                        }
+                        list.finish()
+                    }
+                }
+            }
+            #map_entry
+            Truncated {
+                iter: #iter,
+                max: #max,
+                set: #set,
+            }
+        }
+    }
 }
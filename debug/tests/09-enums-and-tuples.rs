@@ -0,0 +1,63 @@
+// Besides structs with named fields, CustomDebug supports tuple structs, unit
+// structs and enums with any kind of variant. The output is identical to that
+// of std's #[derive(Debug)], in both normal and alternate mode, except where a
+// field carries its own #[debug = "..."] format.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+mod std_derive {
+    #[derive(Debug)]
+    pub struct Tuple(pub u8, pub &'static str);
+
+    #[derive(Debug)]
+    pub struct Unit;
+
+    #[derive(Debug)]
+    pub struct Empty {}
+
+    #[derive(Debug)]
+    pub enum Error<T> {
+        Eof,
+        Io(T, u32),
+        Parse { line: usize, message: String },
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Tuple(u8, &'static str);
+
+#[derive(CustomDebug)]
+pub struct Unit;
+
+#[derive(CustomDebug)]
+pub struct Empty {}
+
+#[derive(CustomDebug)]
+pub enum Error<T> {
+    Eof,
+    Io(T, #[debug = "0x{:04x}"] u32),
+    Parse { line: usize, message: String },
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn assert_same(ours: impl Debug, std: impl Debug) {
+    assert_eq!(format!("{:?}", ours), format!("{:?}", std));
+    assert_eq!(format!("{:#?}", ours), format!("{:#?}", std));
+}
+
+fn main() {
+    assert_same(Tuple(1, "one"), std_derive::Tuple(1, "one"));
+    assert_same(Unit, std_derive::Unit);
+    assert_same(Empty {}, std_derive::Empty {});
+    assert_same(Error::<()>::Eof, std_derive::Error::<()>::Eof);
+    assert_same(
+        Error::<()>::Parse { line: 3, message: "eof".to_owned() },
+        std_derive::Error::<()>::Parse { line: 3, message: "eof".to_owned() },
+    );
+
+    let debug = format!("{:?}", Error::Io("disk", 42));
+    assert_eq!(debug, r#"Io("disk", 0x002a)"#);
+}
@@ -0,0 +1,62 @@
+// Fields holding secrets can be kept out of the output. #[debug(skip)] leaves
+// a field out entirely and ends the output with `..`, the same as
+// Formatter::debug_struct's finish_non_exhaustive. #[debug(redact)] keeps the
+// field name but prints `***` in place of its value, or the given placeholder
+// with #[debug(redact = "...")].
+//
+// On the type, #[debug(redact_all_except(...))] redacts every field other than
+// the ones listed.
+//
+// Neither skipped nor redacted fields need to implement Debug.
+
+use derive_debug::CustomDebug;
+
+pub struct Secret;
+
+#[derive(CustomDebug)]
+pub struct Credentials {
+    user: &'static str,
+    #[debug(redact)]
+    password: Secret,
+    #[debug(redact = "<hidden>")]
+    #[debug = "{:?}"]
+    pin: u16,
+    #[debug(skip)]
+    token: Secret,
+}
+
+#[derive(CustomDebug)]
+pub struct Tuple(u8, #[debug(skip)] Secret);
+
+#[derive(CustomDebug)]
+#[debug(redact_all_except(host, port))]
+pub struct Connection {
+    host: &'static str,
+    port: u16,
+    user: &'static str,
+    password: Secret,
+}
+
+fn main() {
+    let credentials = Credentials {
+        user: "root",
+        password: Secret,
+        pin: 1234,
+        token: Secret,
+    };
+    let debug = format!("{:?}", credentials);
+    let expected = r#"Credentials { user: "root", password: ***, pin: <hidden>, .. }"#;
+    assert_eq!(debug, expected);
+
+    assert_eq!(format!("{:?}", Tuple(1, Secret)), "Tuple(1, ..)");
+
+    let connection = Connection {
+        host: "localhost",
+        port: 5432,
+        user: "postgres",
+        password: Secret,
+    };
+    let debug = format!("{:?}", connection);
+    let expected = r#"Connection { host: "localhost", port: 5432, user: ***, password: *** }"#;
+    assert_eq!(debug, expected);
+}
@@ -0,0 +1,65 @@
+// #[debug(with = "path")] formats a field by calling a function of type
+// `fn(&T, &mut fmt::Formatter) -> fmt::Result`, so the field's type doesn't
+// need a Debug impl of the desired shape.
+//
+// A #[debug = "..."] format string may also refer to other fields of the same
+// struct or variant as `{self.name}` (or `{self.0}` in a tuple struct), with
+// or without `{}` for the field itself.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+use std::time::Duration;
+
+mod fmt_util {
+    use std::fmt;
+    use std::time::Duration;
+
+    pub fn duration(duration: &Duration, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}ms", duration.as_millis())
+    }
+
+    pub fn hex_dump(bytes: &Vec<u8>, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in bytes {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Opaque;
+
+fn opaque(_: &Opaque, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("opaque")
+}
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    #[debug(with = "fmt_util::duration")]
+    elapsed: Duration,
+    #[debug(with = "fmt_util::hex_dump")]
+    payload: Vec<u8>,
+    #[debug = "{self.payload_len} bytes"]
+    len: usize,
+    #[debug = "{}/{self.len}"]
+    payload_len: usize,
+    #[debug(with = "opaque")]
+    inner: Opaque,
+}
+
+#[derive(CustomDebug)]
+pub struct Range(u32, #[debug = "{self.0}..{}"] u32);
+
+fn main() {
+    let packet = Packet {
+        elapsed: Duration::from_millis(1500),
+        payload: vec![0xde, 0xad, 0xbe, 0xef],
+        len: 8,
+        payload_len: 4,
+        inner: Opaque,
+    };
+    let debug = format!("{:?}", packet);
+    let expected = "Packet { elapsed: 1500ms, payload: deadbeef, len: 4 bytes, payload_len: 4/8, inner: opaque }";
+    assert_eq!(debug, expected);
+
+    assert_eq!(format!("{:?}", Range(1, 5)), "Range(1, 1..5)");
+}
@@ -0,0 +1,71 @@
+// Bound inference beyond the heuristics of tests 05 and 07:
+//
+//   - qualified paths such as `<T as Trait>::Value` are bounded like `T::Value`,
+//   - associated types nested in other generic types are found,
+//   - fn pointers are Debug whatever their parameters, so need no bound,
+//   - a field holding a trait object over a type parameter is bounded as a
+//     whole, e.g. `Box<dyn Shape<T>>: Debug`, since no bound on T can help.
+//
+// A #[debug(bound = "...")] on a field replaces only the bounds inferred from
+// that field, and may be empty.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+pub trait Trait {
+    type Value;
+}
+
+pub trait Shape<T>: Debug {}
+
+pub struct Handle<T>(PhantomData<T>);
+
+impl<T> Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Handle")
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Qualified<T: Trait> {
+    value: <T as Trait>::Value,
+    nested: Option<Box<T::Value>>,
+    callback: fn(T) -> T,
+}
+
+#[derive(CustomDebug)]
+pub struct Dynamic<T> {
+    shape: Box<dyn Shape<T>>,
+}
+
+#[derive(CustomDebug)]
+pub struct Override<T> {
+    #[debug(bound = "")]
+    handle: Handle<T>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct NotDebug;
+
+    impl Trait for NotDebug {
+        type Value = u8;
+    }
+
+    #[derive(Debug)]
+    struct Square;
+
+    impl Shape<NotDebug> for Square {}
+
+    assert_debug::<Qualified<NotDebug>>();
+    assert_debug::<Dynamic<NotDebug>>();
+    assert_debug::<Override<NotDebug>>();
+
+    let dynamic = Dynamic::<NotDebug> {
+        shape: Box::new(Square),
+    };
+    assert_eq!(format!("{:?}", dynamic), "Dynamic { shape: Square }");
+}
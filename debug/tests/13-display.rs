@@ -0,0 +1,57 @@
+// The crate also provides a CustomDisplay derive. The struct, or each variant
+// of an enum, carries a #[display("...")] format string in which named fields
+// are referred to by name and tuple fields by index.
+//
+// Type parameters are bounded the same way as for CustomDebug, with the trait
+// required by each placeholder's format spec: Display for `{name}`, Debug for
+// `{name:?}`, LowerHex for `{name:x}` and so on.
+
+use derive_debug::CustomDisplay;
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+#[derive(CustomDisplay)]
+pub enum Error<E> {
+    #[display("Failed to open {path}: {source}")]
+    Open { path: String, source: E },
+    #[display("Unexpected byte 0x{0:02x} at offset {1}")]
+    Byte(u8, usize),
+    #[display("Unsupported version {0:?}")]
+    Version(Option<u32>),
+    #[display("End of file")]
+    Eof,
+}
+
+#[derive(CustomDisplay)]
+#[display("{name} {{ len: {len} }}")]
+pub struct Table<T> {
+    name: &'static str,
+    len: usize,
+    rows: PhantomData<T>,
+}
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    // Does not implement Display.
+    struct NotDisplay;
+
+    assert_display::<Error<String>>();
+    assert_display::<Table<NotDisplay>>();
+
+    let open = Error::Open {
+        path: "/etc/hosts".to_owned(),
+        source: "permission denied",
+    };
+    assert_eq!(open.to_string(), "Failed to open /etc/hosts: permission denied");
+    assert_eq!(Error::<String>::Byte(0xff, 12).to_string(), "Unexpected byte 0xff at offset 12");
+    assert_eq!(Error::<String>::Version(Some(3)).to_string(), "Unsupported version Some(3)");
+    assert_eq!(Error::<String>::Eof.to_string(), "End of file");
+
+    let table = Table::<NotDisplay> {
+        name: "users",
+        len: 3,
+        rows: PhantomData,
+    };
+    assert_eq!(table.to_string(), "users { len: 3 }");
+}
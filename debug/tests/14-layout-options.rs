@@ -0,0 +1,59 @@
+// Container options controlling the layout of the output:
+//
+//   - #[debug(compact)] prints on a single line even in alternate ({:#?})
+//     mode,
+//   - #[debug(max_items = N)] prints at most N items of each collection field
+//     (Vec, VecDeque, LinkedList, BinaryHeap, HashSet, BTreeSet, HashMap,
+//     BTreeMap, arrays and slices) and summarizes the rest as `... (M more)`,
+//   - #[debug(hex)] prints every integer field as `{:#x}`.
+//
+// Fields with their own format keep it.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeMap;
+
+#[derive(CustomDebug)]
+#[debug(compact)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_items = 3)]
+pub struct Buffer {
+    bytes: Vec<u8>,
+    short: [u8; 2],
+    index: BTreeMap<&'static str, u8>,
+}
+
+#[derive(CustomDebug)]
+#[debug(hex)]
+pub struct Register {
+    name: &'static str,
+    address: u32,
+    #[debug = "{}"]
+    width: u8,
+}
+
+fn main() {
+    let point = Point { x: 1, y: -2 };
+    assert_eq!(format!("{:#?}", point), "Point { x: 1, y: -2 }");
+
+    let buffer = Buffer {
+        bytes: vec![1, 2, 3, 4, 5],
+        short: [6, 7],
+        index: [("a", 1), ("b", 2), ("c", 3), ("d", 4)].into_iter().collect(),
+    };
+    let debug = format!("{:?}", buffer);
+    let expected = "Buffer { bytes: [1, 2, 3, ... (2 more)], short: [6, 7], index: {\"a\": 1, \"b\": 2, \"c\": 3, ... (1 more)} }";
+    assert_eq!(debug, expected);
+
+    let register = Register {
+        name: "CTRL",
+        address: 0x4000_0000,
+        width: 32,
+    };
+    let debug = format!("{:?}", register);
+    assert_eq!(debug, r#"Register { name: "CTRL", address: 0x40000000, width: 32 }"#);
+}
@@ -0,0 +1,68 @@
+// Fields of a #[repr(packed)] struct may be unaligned, so taking references to
+// them is not allowed. For packed structs the generated impl copies each
+// printed field out of the struct first, which requires the field types to be
+// Copy.
+//
+// Unions are supported too. A #[debug(tag = "...")] function of type
+// `fn(&Self) -> &str` names the active field, which is then printed like the
+// only field of a struct. Without a tag, or if the tag names no field, no
+// field can be read safely, so the union is printed as `Name { .. }`.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug, Clone, Copy)]
+#[repr(C, packed)]
+pub struct Header {
+    kind: u8,
+    #[debug = "{:#06x}"]
+    len: u32,
+}
+
+#[derive(CustomDebug)]
+#[repr(packed(2))]
+pub struct Wrapper {
+    header: Header,
+    #[debug(skip)]
+    checksum: u64,
+}
+
+#[derive(CustomDebug)]
+#[debug(tag = "Value::active")]
+pub union Value {
+    int: u32,
+    #[debug = "{:.1}"]
+    float: f32,
+}
+
+impl Value {
+    fn active(&self) -> &'static str {
+        if unsafe { self.int } > 0x3f00_0000 {
+            "float"
+        } else {
+            "int"
+        }
+    }
+}
+
+#[derive(CustomDebug)]
+pub union Register {
+    word: u16,
+    bytes: [u8; 2],
+}
+
+fn main() {
+    let header = Header { kind: 1, len: 64 };
+    assert_eq!(format!("{:?}", header), "Header { kind: 1, len: 0x0040 }");
+
+    let wrapper = Wrapper { header, checksum: 0 };
+    assert_eq!(
+        format!("{:?}", wrapper),
+        "Wrapper { header: Header { kind: 1, len: 0x0040 }, .. }",
+    );
+
+    assert_eq!(format!("{:?}", Value { int: 7 }), "Value { int: 7 }");
+    assert_eq!(format!("{:?}", Value { float: 1.5 }), "Value { float: 1.5 }");
+
+    let register = Register { bytes: [0x12, 0xab] };
+    assert_eq!(format!("{:?}", register), "Register { .. }");
+}
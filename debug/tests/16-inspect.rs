@@ -0,0 +1,54 @@
+// Besides formatting, it is sometimes useful to walk over the fields of a
+// value, for example to emit them as structured log fields. Deriving Inspect
+// generates an inherent method
+//
+//     pub fn inspect(&self, visitor: &mut dyn FnMut(&str, &dyn Debug))
+//
+// which calls the visitor with the name and value of each field, in
+// declaration order. The #[debug] attributes apply exactly as for CustomDebug:
+// skipped fields are not visited, redacted fields are visited with their
+// placeholder, and formatted fields with their formatted value. Tuple fields
+// are named by their index.
+
+use derive_debug::{CustomDebug, Inspect};
+
+#[derive(CustomDebug, Inspect)]
+pub struct Request {
+    method: &'static str,
+    #[debug = "0b{:08b}"]
+    flags: u8,
+    #[debug(redact)]
+    token: String,
+    #[debug(skip)]
+    cache: Vec<u8>,
+}
+
+#[derive(Inspect)]
+pub enum Event {
+    Ping,
+    Move(i32, i32),
+}
+
+fn collect(inspect: impl FnOnce(&mut dyn FnMut(&str, &dyn std::fmt::Debug))) -> Vec<String> {
+    let mut fields = Vec::new();
+    inspect(&mut |name, value| fields.push(format!("{}={:?}", name, value)));
+    fields
+}
+
+fn main() {
+    let request = Request {
+        method: "GET",
+        flags: 5,
+        token: "secret".to_owned(),
+        cache: Vec::new(),
+    };
+    assert_eq!(
+        collect(|v| request.inspect(v)),
+        ["method=\"GET\"", "flags=0b00000101", "token=***"],
+    );
+
+    assert!(collect(|v| Event::Ping.inspect(v)).is_empty());
+    assert_eq!(collect(|v| Event::Move(1, -2).inspect(v)), ["0=1", "1=-2"]);
+
+    let _ = request.cache;
+}
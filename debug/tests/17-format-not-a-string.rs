@@ -0,0 +1,14 @@
+// The value of `#[debug = ...]` must be a format string. Anything else is
+// reported at the offending literal rather than producing an error from
+// inside the generated code. This is a compile_fail test.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Field {
+    name: &'static str,
+    #[debug = 5]
+    bitmask: u8,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// The value of `bound = ...` is a string of where-predicates, on the type as
+// well as on a field. This is a compile_fail test.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T> {
+    #[debug(bound = 3)]
+    value: T,
+}
+
+fn main() {}
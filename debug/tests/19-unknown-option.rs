@@ -0,0 +1,14 @@
+// Misspelled or unknown options inside `#[debug(...)]` are errors listing the
+// options that are accepted, spanned at the option. This is a compile_fail
+// test.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Session {
+    user: String,
+    #[debug(redcat)]
+    password: String,
+}
+
+fn main() {}
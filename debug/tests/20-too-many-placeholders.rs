@@ -0,0 +1,14 @@
+// The format string of a field has a single positional argument, the field's
+// value. A second `{}` would otherwise fail inside the generated format_args!
+// call with an error that does not mention the attribute; instead it is
+// reported at the format string. This is a compile_fail test.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Point {
+    #[debug = "({}, {})"]
+    coordinates: (i32, i32),
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+// A format string may refer to other fields as `{self.name}`, but not to a
+// field that is skipped or redacted, including one redacted by
+// #[debug(redact_all_except(...))]. Otherwise the hidden value would be
+// printed through its sibling. This is a compile_fail test.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Creds {
+    #[debug(redact)]
+    password: String,
+    #[debug = "{} (pw={self.password})"]
+    user: String,
+}
+
+#[derive(CustomDebug)]
+#[debug(redact_all_except(user))]
+pub struct Session {
+    token: String,
+    #[debug = "{} ({self.token})"]
+    user: String,
+}
+
+fn main() {}
@@ -1,12 +1,25 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-impl-debug.rs");
-    //t.pass("tests/03-custom-format.rs");
-    //t.pass("tests/04-type-parameter.rs");
-    //t.pass("tests/05-phantom-data.rs");
-    //t.pass("tests/06-bound-trouble.rs");
-    //t.pass("tests/07-associated-type.rs");
-    //t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-impl-debug.rs");
+    t.pass("tests/03-custom-format.rs");
+    t.pass("tests/04-type-parameter.rs");
+    t.pass("tests/05-phantom-data.rs");
+    t.pass("tests/06-bound-trouble.rs");
+    t.pass("tests/07-associated-type.rs");
+    t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-enums-and-tuples.rs");
+    t.pass("tests/10-skip-redact.rs");
+    t.pass("tests/11-with-and-siblings.rs");
+    t.pass("tests/12-bound-inference.rs");
+    t.pass("tests/13-display.rs");
+    t.pass("tests/14-layout-options.rs");
+    t.pass("tests/15-packed-and-unions.rs");
+    t.pass("tests/16-inspect.rs");
+    t.compile_fail("tests/17-format-not-a-string.rs");
+    t.compile_fail("tests/18-bound-not-a-string.rs");
+    t.compile_fail("tests/19-unknown-option.rs");
+    t.compile_fail("tests/20-too-many-placeholders.rs");
+    t.compile_fail("tests/21-redacted-sibling.rs");
 }
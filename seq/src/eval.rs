@@ -0,0 +1,59 @@
+use proc_macro2::Ident;
+use syn::{BinOp, Expr, Lit, UnOp};
+
+/// Evaluates the integer expression of a `#{ ... }` block, looking up
+/// variables with `var`. Returns `None` if a variable has no value yet.
+pub fn evaluate(expr: &Expr, var: &dyn Fn(&Ident) -> syn::Result<Option<i128>>) -> syn::Result<Option<i128>> {
+    let value = match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => int.base10_parse()?,
+            lit => return Err(syn::Error::new_spanned(lit, "expected an integer")),
+        },
+        Expr::Path(path) if path.qself.is_none() && path.path.get_ident().is_some() => {
+            match var(path.path.get_ident().unwrap())? {
+                Some(value) => value,
+                None => return Ok(None),
+            }
+        }
+        Expr::Paren(paren) => return evaluate(&paren.expr, var),
+        Expr::Group(group) => return evaluate(&group.expr, var),
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => match evaluate(&unary.expr, var)? {
+            Some(value) => value.checked_neg().ok_or_else(|| overflow(expr))?,
+            None => return Ok(None),
+        },
+        Expr::Binary(binary) => {
+            let (lhs, rhs) = match (evaluate(&binary.left, var)?, evaluate(&binary.right, var)?) {
+                (Some(lhs), Some(rhs)) => (lhs, rhs),
+                _ => return Ok(None),
+            };
+            let value = match binary.op {
+                BinOp::Add(_) => lhs.checked_add(rhs),
+                BinOp::Sub(_) => lhs.checked_sub(rhs),
+                BinOp::Mul(_) => lhs.checked_mul(rhs),
+                BinOp::Div(_) | BinOp::Rem(_) if rhs == 0 => {
+                    return Err(syn::Error::new_spanned(expr, "attempt to divide by zero"));
+                }
+                BinOp::Div(_) => lhs.checked_div(rhs),
+                BinOp::Rem(_) => lhs.checked_rem(rhs),
+                BinOp::Shl(_) => u32::try_from(rhs)
+                    .ok()
+                    .and_then(|rhs| 2i128.checked_pow(rhs))
+                    .and_then(|factor| lhs.checked_mul(factor)),
+                BinOp::Shr(_) => u32::try_from(rhs).ok().map(|rhs| lhs >> rhs.min(127)),
+                op => {
+                    return Err(syn::Error::new_spanned(
+                        op,
+                        "expected one of `+`, `-`, `*`, `/`, `%`, `<<` or `>>`",
+                    ))
+                }
+            };
+            value.ok_or_else(|| overflow(expr))?
+        }
+        expr => return Err(syn::Error::new_spanned(expr, "expected an integer expression")),
+    };
+    Ok(Some(value))
+}
+
+fn overflow(expr: &Expr) -> syn::Error {
+    syn::Error::new_spanned(expr, "arithmetic overflow evaluating this expression")
+}
@@ -0,0 +1,511 @@
+use crate::{eval, format};
+use crate::parse::{Seq, Value, Var};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream as TokenStream2, TokenTree};
+use std::cell::Cell;
+use syn::{Expr, LitInt, LitStr};
+
+/// The loop variables bound at some point of the body, with the position of
+/// their value.
+type Env<'a> = Vec<(&'a Var, usize)>;
+
+impl Seq {
+    pub fn expand(&self) -> syn::Result<TokenStream2> {
+        let mut repeated = false;
+        let expanded = self.expand_stream(self.body.clone(), &Env::new(), false, &mut repeated)?;
+        if repeated {
+            return Ok(expanded);
+        }
+        let mut out = TokenStream2::new();
+        for env in self.product(&Env::new()) {
+            out.extend(self.expand_stream(self.body.clone(), &env, false, &mut repeated)?);
+        }
+        Ok(out)
+    }
+
+    /// Every combination of values of the variables not bound in `env`, each
+    /// appended to `env`.
+    fn product<'a>(&'a self, env: &Env<'a>) -> Vec<Env<'a>> {
+        let mut envs = vec![env.clone()];
+        for var in &self.vars {
+            if env.iter().any(|(bound, _)| std::ptr::eq(*bound, var)) {
+                continue;
+            }
+            envs = envs
+                .into_iter()
+                .flat_map(|env| {
+                    (0..var.values.len()).map(move |position| {
+                        let mut env = env.clone();
+                        env.push((var, position));
+                        env
+                    })
+                })
+                .collect();
+        }
+        envs
+    }
+
+    /// Substitutes the variables bound in `env` and expands the repeated
+    /// sections of `stream`, recording in `repeated` whether there was any.
+    /// Within the body of a nested `seq!` invocation, `nested` is set:
+    /// unlabelled sections are left to that invocation, and pastes are only
+    /// substituted, not joined, since they may involve its variables.
+    fn expand_stream<'a>(
+        &'a self,
+        stream: TokenStream2,
+        env: &Env<'a>,
+        nested: bool,
+        repeated: &mut bool,
+    ) -> syn::Result<TokenStream2> {
+        let tokens: Vec<TokenTree> = stream.into_iter().collect();
+        let mut out = TokenStream2::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            if let Some(repetition) = repetition(&tokens[i..]) {
+                let envs = match repetition.label {
+                    None if !nested => Some(self.product(env)),
+                    None => None,
+                    Some(label) => self.vars.iter().find(|var| var.binds(label)).map(|var| {
+                        let envs = (0..var.values.len()).map(|position| {
+                            let mut env = env.clone();
+                            env.push((var, position));
+                            env
+                        });
+                        envs.collect()
+                    }),
+                };
+                if let (None, Some(label), false) = (&envs, repetition.label, nested) {
+                    let msg = format!("cannot repeat over `{}`, which is not a variable of this seq!", label);
+                    return Err(syn::Error::new(label.span(), msg));
+                }
+                if let Some(envs) = envs {
+                    *repeated = true;
+                    let section = repetition.group.stream();
+                    for (k, env) in envs.into_iter().enumerate() {
+                        if k > 0 {
+                            out.extend(repetition.separator.clone());
+                        }
+                        out.extend(self.expand_stream(section.clone(), &env, nested, repeated)?);
+                    }
+                    i += repetition.len;
+                    continue;
+                }
+            }
+            if let Some(section) = self.unterminated(&tokens[i..], nested) {
+                let msg = "expected `*` after the repeated section, as in `#( ... )*`";
+                return Err(syn::Error::new_spanned(section, msg));
+            }
+            if let Some(group) = expression(&tokens[i..]) {
+                if let Some(value) = self.evaluate(group, env, nested)? {
+                    out.extend(literal(value, None, group.span()));
+                    i += 2;
+                    continue;
+                }
+            }
+            if let Some((pasted, len)) = self.paste(&tokens[i..], env, nested)? {
+                out.extend(pasted);
+                i += len;
+                continue;
+            }
+            match &tokens[i] {
+                TokenTree::Ident(ident) => match lookup(env, ident) {
+                    Some(Value::Int(value)) => out.extend(literal(value, self.ty(ident), ident.span())),
+                    Some(Value::Tokens(tokens)) => out.extend(tokens),
+                    None => out.extend([tokens[i].clone()]),
+                },
+                TokenTree::Group(group) => {
+                    let nested = nested || is_seq_call(&tokens[..i]);
+                    let stream = self.expand_stream(group.stream(), env, nested, repeated)?;
+                    out.extend([TokenTree::Group(with_stream(group, stream))]);
+                }
+                TokenTree::Literal(lit) => out.extend([TokenTree::Literal(self.interpolate(lit, env)?)]),
+                // Tildes of pastes involving variables of this invocation have
+                // been consumed by `paste` above.
+                TokenTree::Punct(tilde) if tilde.as_char() == '~' && !nested => {
+                    let after_piece = i > 0 && piece_len(&tokens[i - 1..]).is_some();
+                    return Err(match (after_piece, tokens.get(i + 1)) {
+                        (false, _) | (_, None) => syn::Error::new(
+                            tilde.span(),
+                            "`~` pastes a variable onto an identifier, as in `prefix~N`",
+                        ),
+                        (true, Some(next)) => {
+                            let msg = format!("expected a variable of this seq! after `~`, found `{}`", next);
+                            syn::Error::new(next.span(), msg)
+                        }
+                    });
+                }
+                token => out.extend([token.clone()]),
+            }
+            i += 1;
+        }
+        Ok(out)
+    }
+
+    /// Expands a paste chain like `prefix~N`, `cell_~R~_~C`, `reg_~#{N + 1}`
+    /// or `reg_~{N:02x}` at the start of `tokens`, returning the expansion
+    /// and the number of tokens consumed.
+    ///
+    /// A chain is only expanded when it involves a variable of this
+    /// invocation, an expression, a formatted value or a literal, such as a
+    /// value substituted by an enclosing invocation, and only joined into an
+    /// identifier once all of its variables are bound.
+    fn paste(
+        &self,
+        tokens: &[TokenTree],
+        env: &Env,
+        nested: bool,
+    ) -> syn::Result<Option<(TokenStream2, usize)>> {
+        let mut len = match piece_len(tokens) {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+        let mut pieces = Vec::new();
+        pieces.push(0..len);
+        while is_tilde(tokens.get(len)) {
+            match piece_len(&tokens[len + 1..]) {
+                Some(piece_len) => {
+                    pieces.push(len + 1..len + 1 + piece_len);
+                    len += 1 + piece_len;
+                }
+                None => break,
+            }
+        }
+        if pieces.len() == 1 {
+            return Ok(None);
+        }
+
+        let span = tokens[0].span();
+        let mut name = String::new();
+        let mut substituted = TokenStream2::new();
+        let mut expands = false;
+        let mut unbound = false;
+        for (i, range) in pieces.into_iter().enumerate() {
+            if i > 0 {
+                substituted.extend([tokens[range.start - 1].clone()]);
+            }
+            let piece = &tokens[range];
+            let value = match piece {
+                [TokenTree::Ident(ident)] => match lookup(env, ident) {
+                    Some(Value::Int(value)) => Some(value),
+                    Some(Value::Tokens(tokens)) => {
+                        expands = true;
+                        name += &tokens_text(&tokens);
+                        substituted.extend(tokens);
+                        continue;
+                    }
+                    None => None,
+                },
+                [TokenTree::Literal(_) | TokenTree::Group(_)] => {
+                    expands = true;
+                    None
+                }
+                _ => {
+                    expands = true;
+                    let group = expression(piece).unwrap();
+                    self.evaluate(group, env, nested)?
+                }
+            };
+            match value {
+                Some(value) if value < 0 => {
+                    let msg = format!("cannot paste negative value {} onto an identifier", value);
+                    return Err(syn::Error::new(piece[0].span(), msg));
+                }
+                Some(value) => {
+                    expands = true;
+                    name += &value.to_string();
+                    substituted.extend(literal(value, None, piece[0].span()));
+                }
+                None => {
+                    let formatted = match piece {
+                        [TokenTree::Group(group)] => self.format(group, env, nested)?,
+                        _ => None,
+                    };
+                    if let Some(formatted) = formatted {
+                        name += &formatted;
+                        let piece = piece.iter().cloned().collect();
+                        substituted.extend(self.expand_stream(piece, env, nested, &mut false)?);
+                        continue;
+                    }
+                    let var = match piece {
+                        [TokenTree::Ident(ident)] => self.is_var(ident),
+                        [TokenTree::Literal(_)] => false,
+                        _ => true,
+                    };
+                    unbound |= var;
+                    expands |= var;
+                    name += &piece[0].to_string();
+                    let piece = piece.iter().cloned().collect();
+                    substituted.extend(self.expand_stream(piece, env, nested, &mut false)?);
+                }
+            }
+        }
+        if !expands {
+            return Ok(None);
+        }
+        if nested || unbound {
+            return Ok(Some((substituted, len)));
+        }
+        if !is_ident(&name) {
+            let msg = format!("pasting produces `{}`, which is not a valid identifier", name);
+            return Err(syn::Error::new(span, msg));
+        }
+        let ident = Ident::new(&name, span);
+        Ok(Some((TokenTree::Ident(ident).into(), len)))
+    }
+
+    /// The value of the expression of a `#{ ... }` block, or `None` if it
+    /// involves variables that are not bound yet.
+    fn evaluate(&self, group: &Group, env: &Env, nested: bool) -> syn::Result<Option<i128>> {
+        self.evaluate_expr(&syn::parse2(group.stream())?, env, nested)
+    }
+
+    fn evaluate_expr(&self, expr: &Expr, env: &Env, nested: bool) -> syn::Result<Option<i128>> {
+        eval::evaluate(expr, &|ident| match lookup(env, ident) {
+            Some(Value::Int(value)) => Ok(Some(value)),
+            Some(Value::Tokens(_)) => {
+                let msg = format!("`{}` is an item of a list, not an integer", ident);
+                Err(syn::Error::new(ident.span(), msg))
+            }
+            None if nested || self.is_var(ident) => Ok(None),
+            None => {
+                let msg = format!("cannot find variable `{}` in this seq!", ident);
+                Err(syn::Error::new(ident.span(), msg))
+            }
+        })
+    }
+
+    /// The formatted value of a `{EXPR:SPEC}` paste piece, or `None` if the
+    /// expression involves variables that are not bound yet.
+    fn format(&self, group: &Group, env: &Env, nested: bool) -> syn::Result<Option<String>> {
+        let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+        let colon = tokens
+            .iter()
+            .position(|token| matches!(token, TokenTree::Punct(colon) if colon.as_char() == ':'));
+        let (expr, spec) = match colon {
+            Some(colon) => (&tokens[..colon], &tokens[colon + 1..]),
+            None => (&tokens[..], &[][..]),
+        };
+        let expr = syn::parse2(expr.iter().cloned().collect())?;
+        let value = match self.evaluate_expr(&expr, env, nested)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let spec: String = spec.iter().map(ToString::to_string).collect();
+        match format::format_value(value, &spec) {
+            Ok(formatted) => Ok(Some(formatted)),
+            Err(msg) => Err(syn::Error::new(group.span(), msg)),
+        }
+    }
+
+    /// Replaces the `{EXPR}` and `{EXPR:SPEC}` placeholders of a string
+    /// literal whose expression involves bound variables. Other placeholders,
+    /// such as those of a format string, are left alone.
+    fn interpolate(&self, lit: &Literal, env: &Env) -> syn::Result<Literal> {
+        let string = match syn::parse2::<LitStr>(TokenTree::Literal(lit.clone()).into()) {
+            Ok(string) => string,
+            Err(_) => return Ok(lit.clone()),
+        };
+        let interpolated = format::interpolate(&string.value(), |placeholder| {
+            if let Ok(ident) = syn::parse_str::<Ident>(placeholder) {
+                if let Some(Value::Tokens(tokens)) = lookup(env, &ident) {
+                    return Ok(Some(tokens_text(&tokens)));
+                }
+            }
+            let (expr, spec) = placeholder.split_once(':').unwrap_or((placeholder, ""));
+            let expr = match syn::parse_str(expr) {
+                Ok(expr) => expr,
+                Err(_) => return Ok(None),
+            };
+            let uses_var = Cell::new(false);
+            let value = eval::evaluate(&expr, &|ident| match lookup(env, ident) {
+                Some(Value::Int(value)) => {
+                    uses_var.set(true);
+                    Ok(Some(value))
+                }
+                _ => Ok(None),
+            });
+            match value {
+                Ok(Some(value)) if uses_var.get() => format::format_value(value, spec).map(Some),
+                _ => Ok(None),
+            }
+        });
+        match interpolated {
+            Ok(Some(interpolated)) => Ok(LitStr::new(&interpolated, lit.span()).token()),
+            Ok(None) => Ok(lit.clone()),
+            Err(msg) => Err(syn::Error::new(lit.span(), msg)),
+        }
+    }
+
+    /// The `#( ... )` or `#N( ... )` at the start of `tokens` if it is not
+    /// followed by `*`, unless it may belong to a nested invocation.
+    fn unterminated(&self, tokens: &[TokenTree], nested: bool) -> Option<TokenStream2> {
+        let len = match tokens {
+            [TokenTree::Punct(pound), TokenTree::Group(group), ..] if !nested => {
+                pound.as_char() == '#' && group.delimiter() == Delimiter::Parenthesis
+            }
+            .then_some(2),
+            [TokenTree::Punct(pound), TokenTree::Ident(label), TokenTree::Group(group), ..] => {
+                let section = pound.as_char() == '#' && group.delimiter() == Delimiter::Parenthesis;
+                (section && self.is_var(label)).then_some(3)
+            }
+            _ => None,
+        }?;
+        if repetition(tokens).is_some() {
+            return None;
+        }
+        Some(tokens[..len].iter().cloned().collect())
+    }
+
+    fn is_var(&self, ident: &Ident) -> bool {
+        self.vars.iter().any(|var| var.binds(ident))
+    }
+
+    /// The type of the variable `ident`, if it has one.
+    fn ty(&self, ident: &Ident) -> Option<&Ident> {
+        let var = self.vars.iter().find(|var| var.ident == *ident)?;
+        var.ty.as_ref()
+    }
+}
+
+/// The value bound to `ident`, the innermost binding winning.
+fn lookup(env: &Env, ident: &Ident) -> Option<Value> {
+    env.iter().rev().find_map(|&(var, position)| {
+        if var.ident == *ident {
+            Some(var.values[position].clone())
+        } else if var.index.as_ref() == Some(ident) {
+            Some(Value::Int(position as i128))
+        } else {
+            None
+        }
+    })
+}
+
+/// The text of the tokens of a list item, without spaces between tokens.
+fn tokens_text(tokens: &TokenStream2) -> String {
+    tokens.clone().into_iter().map(|token| token.to_string()).collect()
+}
+
+/// A `#( ... )*` section, possibly labelled as in `#LABEL( ... )*` and with a
+/// separator as in `#( ... ),*`.
+struct Repetition<'a> {
+    label: Option<&'a Ident>,
+    group: &'a Group,
+    /// Punctuation emitted between repetitions, like `,`, `+` or `&&`.
+    separator: TokenStream2,
+    /// Number of tokens of the section.
+    len: usize,
+}
+
+/// The repeated section at the start of `tokens`, if any.
+fn repetition(tokens: &[TokenTree]) -> Option<Repetition<'_>> {
+    let (label, rest) = match tokens {
+        [TokenTree::Punct(pound), TokenTree::Ident(label), rest @ ..] if pound.as_char() == '#' => {
+            (Some(label), rest)
+        }
+        [TokenTree::Punct(pound), rest @ ..] if pound.as_char() == '#' => (None, rest),
+        _ => return None,
+    };
+    let group = match rest.first() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => group,
+        _ => return None,
+    };
+
+    // The separator is the punctuation joined to the `*`. The first `*` ends
+    // the section, so that `)*;` is a section followed by a `;`.
+    let mut separator = Vec::new();
+    let mut puncts = rest[1..].iter();
+    loop {
+        let punct = match puncts.next() {
+            Some(TokenTree::Punct(punct)) => punct,
+            _ => return None,
+        };
+        if punct.as_char() == '*' {
+            break;
+        }
+        if punct.spacing() == Spacing::Alone {
+            return None;
+        }
+        separator.push(punct.clone());
+    }
+    let len = usize::from(label.is_some()) + 3 + separator.len();
+    if let Some(last) = separator.last_mut() {
+        let mut alone = Punct::new(last.as_char(), Spacing::Alone);
+        alone.set_span(last.span());
+        *last = alone;
+    }
+    let separator = separator.into_iter().map(TokenTree::Punct).collect();
+    Some(Repetition { label, group, separator, len })
+}
+
+/// Whether `tokens` end with `seq!`, so that a group following them is the
+/// input of a nested invocation.
+fn is_seq_call(tokens: &[TokenTree]) -> bool {
+    match tokens {
+        [.., TokenTree::Ident(name), TokenTree::Punct(bang)] => name == "seq" && bang.as_char() == '!',
+        _ => false,
+    }
+}
+
+/// The braced group of a `#{ ... }` expression at the start of `tokens`.
+fn expression(tokens: &[TokenTree]) -> Option<&Group> {
+    match tokens {
+        [TokenTree::Punct(pound), TokenTree::Group(group), ..]
+            if pound.as_char() == '#' && group.delimiter() == Delimiter::Brace =>
+        {
+            Some(group)
+        }
+        _ => None,
+    }
+}
+
+fn is_tilde(token: Option<&TokenTree>) -> bool {
+    matches!(token, Some(TokenTree::Punct(tilde)) if tilde.as_char() == '~')
+}
+
+/// The number of tokens of the paste piece at the start of `tokens`: an
+/// identifier, a literal, a `#{ ... }` expression or a `{ ... }` formatted
+/// value.
+fn piece_len(tokens: &[TokenTree]) -> Option<usize> {
+    match tokens {
+        [TokenTree::Ident(_) | TokenTree::Literal(_), ..] => Some(1),
+        [TokenTree::Group(group), ..] if group.delimiter() == Delimiter::Brace => Some(1),
+        _ => expression(tokens).map(|_| 2),
+    }
+}
+
+fn is_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    let first_ok = matches!(chars.next(), Some(ch) if ch == '_' || ch.is_alphabetic());
+    first_ok && name != "_" && chars.all(|ch| ch == '_' || ch.is_alphanumeric())
+}
+
+/// An integer literal for `value`, suffixed with `ty` if given or else
+/// inferred where it is used. A negative value is a `-` followed by the
+/// literal, in parentheses so that a method call or a cast in the body, as in
+/// `N.abs()`, applies to the negative value. Rustc does not give invisible
+/// groups that precedence.
+fn literal(value: i128, ty: Option<&Ident>, span: Span) -> TokenStream2 {
+    let literal = match ty {
+        Some(ty) => LitInt::new(&format!("{}{}", value.unsigned_abs(), ty), span).token(),
+        None => {
+            let mut literal = Literal::u128_unsuffixed(value.unsigned_abs());
+            literal.set_span(span);
+            literal
+        }
+    };
+    if value >= 0 {
+        return TokenTree::Literal(literal).into();
+    }
+    let mut minus = Punct::new('-', Spacing::Alone);
+    minus.set_span(span);
+    let stream = [TokenTree::Punct(minus), TokenTree::Literal(literal)].into_iter().collect();
+    let mut group = Group::new(Delimiter::Parenthesis, stream);
+    group.set_span(span);
+    TokenTree::Group(group).into()
+}
+
+/// A copy of `group` with the same delimiter and span around `stream`.
+fn with_stream(group: &Group, stream: TokenStream2) -> Group {
+    let mut copy = Group::new(group.delimiter(), stream);
+    copy.set_span(group.span());
+    copy
+}
@@ -0,0 +1,73 @@
+/// Formats `value` according to a format spec of the form
+/// `[#][0][WIDTH][x|X|o|b]`, like `format!` would.
+pub fn format_value(value: i128, spec: &str) -> Result<String, String> {
+    let unsupported = || format!("unsupported format spec `{}`, expected `[#][0][width][x|X|o|b]`", spec);
+    let rest = spec;
+    let (alternate, rest) = match rest.strip_prefix('#') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let (zero, rest) = match rest.strip_prefix('0') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let digits = rest.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(rest.len());
+    let width = match &rest[..digits] {
+        "" => 0,
+        width => width.parse::<usize>().map_err(|_| unsupported())?,
+    };
+    let abs = value.unsigned_abs();
+    let (prefix, digits) = match &rest[digits..] {
+        "" => ("", abs.to_string()),
+        "x" => ("0x", format!("{:x}", abs)),
+        "X" => ("0x", format!("{:X}", abs)),
+        "o" => ("0o", format!("{:o}", abs)),
+        "b" => ("0b", format!("{:b}", abs)),
+        _ => return Err(unsupported()),
+    };
+    let sign = if value < 0 { "-" } else { "" };
+    let prefix = if alternate { prefix } else { "" };
+    let len = sign.len() + prefix.len() + digits.len();
+    let padding = width.saturating_sub(len);
+    Ok(if zero {
+        format!("{}{}{}{}", sign, prefix, "0".repeat(padding), digits)
+    } else {
+        format!("{}{}{}{}", " ".repeat(padding), sign, prefix, digits)
+    })
+}
+
+/// Replaces the `{...}` placeholders of a string for which `f` returns a
+/// replacement, leaving other placeholders and escaped braces as they are.
+/// Escaped braces are not unescaped because the string may be a format
+/// string. Returns `None` if nothing was replaced.
+pub fn interpolate(
+    string: &str,
+    mut f: impl FnMut(&str) -> Result<Option<String>, String>,
+) -> Result<Option<String>, String> {
+    let mut out = String::with_capacity(string.len());
+    let mut replaced = false;
+    let mut rest = string;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        rest = &rest[open..];
+        if rest.starts_with("{{") {
+            out.push_str("{{");
+            rest = &rest[2..];
+            continue;
+        }
+        let close = match rest.find('}') {
+            Some(close) => close,
+            None => break,
+        };
+        match f(&rest[1..close])? {
+            Some(replacement) => {
+                out.push_str(&replacement);
+                replaced = true;
+            }
+            None => out.push_str(&rest[..=close]),
+        }
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+    Ok(if replaced { Some(out) } else { None })
+}
@@ -1,8 +1,97 @@
+mod eval;
+mod expand;
+mod format;
+mod parse;
+mod unroll;
+
+use crate::parse::Seq;
+use crate::unroll::SeqConst;
 use proc_macro::TokenStream;
+use syn::parse_macro_input;
 
+/// Repeats a body of tokens once for every value of a range, substituting the
+/// value for the loop variable:
+///
+/// ```ignore
+/// seq!(N in 0..4 {
+///     fn f~N() -> u64 { N * 2 }
+/// });
+/// ```
+///
+/// The range may be stepped and reversed, either with `LO..HI step S` or with
+/// the iterator adapters `(LO..HI).step_by(S)` and `(LO..HI).rev()`. Its
+/// bounds are integer literals, possibly negative, or the `BITS`, `MIN` and
+/// `MAX` constants of the fixed-size integer types, like `u8::BITS`; see
+/// `seq_const!` for other constants. At most 65536 values are left after
+/// stepping.
+///
+/// The variable expands to an unsuffixed integer literal, unless the bounds
+/// of its range are suffixed, as in `0u16..8u16`, or it is annotated with a
+/// type, as in `seq!(N: u8 in 0..8 { ... })`. The literal then carries that
+/// suffix, and every value of the range must fit in the type.
+///
+/// Instead of a range, a variable can iterate over a bracketed list of items,
+/// as in `seq!(T in [u8, u16, u32] { ... })`, each item being substituted as
+/// written. `seq!((I, T) in enumerate [...] { ... })` also binds `I` to the
+/// position of each item, and works for ranges as well.
+///
+/// Several variables, as in `seq!(R in 0..4, C in 0..4 { ... })`, iterate
+/// over every combination of their values, the first variable varying
+/// slowest. There may be at most 65536 combinations.
+///
+/// `#{ ... }` evaluates an integer expression of the variables at compile
+/// time, with `+ - * / % << >>` and parentheses, to an unsuffixed literal.
+///
+/// `prefix~N` pastes the value onto an identifier; longer chains such as
+/// `cell_~R~_~C` or `field_~N~_to_~#{N + 1}` paste every piece. A piece
+/// `{EXPR:SPEC}` pastes a formatted value, as in `reg_~{N:02x}`; the spec has
+/// the form `[#][0][width][x|X|o|b]`. Placeholders `{N}` and `{EXPR:SPEC}` in
+/// string literals are replaced the same way, as in `"field {N}"`. Escaped
+/// braces `{{` and `}}` are left as written so that the string can still be
+/// used as a format string; in any other string they stay doubled.
+///
+/// If the body contains `#( ... )*` sections, only those are repeated and the
+/// rest of the body is emitted once. Like in `macro_rules!`, punctuation
+/// before the `*` separates the repetitions, as in `#( ... ),*` or
+/// `#( ... )+*`. A section labelled with a variable, `#R( ... )*`, repeats
+/// over that variable alone, so that sections for different variables can be
+/// nested. Unlabelled sections inside the body of a nested `seq!` invocation
+/// belong to that invocation.
 #[proc_macro]
 pub fn seq(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let seq = parse_macro_input!(input as Seq);
+    seq.expand().unwrap_or_else(|err| err.to_compile_error()).into()
+}
 
-    unimplemented!()
+/// Unrolls a loop whose bounds are constant expressions, such as `const`
+/// items, which `seq!` cannot evaluate:
+///
+/// ```ignore
+/// const LEN: usize = 4;
+///
+/// seq_const!(N in 0..LEN {
+///     sum += buffer[N];
+/// });
+/// ```
+///
+/// The body runs once for every value of the range, in statement position,
+/// with the variable defined as a `const` of type `usize`, or of the type
+/// given as in `N: u32 in ...`, so that it can be used in const contexts such
+/// as array lengths and const generic arguments. Unlike in `seq!`, the body is
+/// not rewritten, so pastes and repeated sections are not available. At most
+/// 64 iterations are unrolled.
+///
+/// The body is copied 64 times, whatever the length of the range, and the
+/// copies past the end of the range are compiled but never run. In those
+/// copies the variable is the start of the range, so the body only has to be
+/// valid for the values of the range.
+///
+/// The bounds cannot mention const generic parameters of the enclosing
+/// function, as in `fn f<const LEN: usize>()`. The variable of each copy is a
+/// `const` item computed from the bounds, and items cannot use the generic
+/// parameters of their parent (E0401).
+#[proc_macro]
+pub fn seq_const(input: TokenStream) -> TokenStream {
+    let seq = parse_macro_input!(input as SeqConst);
+    seq.expand().into()
 }
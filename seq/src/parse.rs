@@ -0,0 +1,426 @@
+use proc_macro2::{Delimiter, Ident, Spacing, TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::buffer::Cursor;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, bracketed, parenthesized, token, LitInt, Token};
+
+/// The input of `seq!`: one or more `N in RANGE`, `N: TYPE in RANGE`,
+/// `T in [LIST]` or `(I, T) in enumerate ...` separated by commas, followed by
+/// the body in braces.
+pub struct Seq {
+    pub vars: Vec<Var>,
+    pub body: TokenStream2,
+}
+
+/// A loop variable and the values it takes, in order.
+pub struct Var {
+    pub ident: Ident,
+    /// Variable bound to the position of the value, for `enumerate`.
+    pub index: Option<Ident>,
+    pub values: Vec<Value>,
+    /// Integer type of the variable, given by an annotation or by the suffix
+    /// of the range bounds, and used as the suffix of substituted literals.
+    pub ty: Option<Ident>,
+}
+
+/// A value of a loop variable: an integer of a range or the tokens of an item
+/// of a list.
+#[derive(Clone)]
+pub enum Value {
+    Int(i128),
+    Tokens(TokenStream2),
+}
+
+impl Var {
+    /// Whether `ident` is this variable or its index.
+    pub fn binds(&self, ident: &Ident) -> bool {
+        self.ident == *ident || self.index.as_ref() == Some(ident)
+    }
+}
+
+impl Parse for Seq {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let header = input.cursor();
+        let mut vars = Vec::<Var>::new();
+        loop {
+            let (index, ident) = if input.peek(token::Paren) {
+                let content;
+                parenthesized!(content in input);
+                let index: Ident = content.parse()?;
+                content.parse::<Token![,]>()?;
+                (Some(index), content.parse()?)
+            } else {
+                (None, input.parse()?)
+            };
+            let bound = |ident: &Ident| vars.iter().any(|var| var.binds(ident));
+            let duplicate = match &index {
+                Some(index) if bound(index) => Some(index),
+                Some(index) if *index == ident => Some(&ident),
+                _ if bound(&ident) => Some(&ident),
+                _ => None,
+            };
+            if let Some(duplicate) = duplicate {
+                let msg = format!("`{}` is bound more than once", duplicate);
+                return Err(syn::Error::new(duplicate.span(), msg));
+            }
+            let mut ty: Option<Ident> = match input.parse::<Option<Token![:]>>()? {
+                Some(_) => Some(input.parse()?),
+                None => None,
+            };
+            input.parse::<Token![in]>()?;
+            let enumerate = input.peek(syn::Ident) && input.fork().parse::<Ident>()? == "enumerate";
+            if enumerate {
+                input.parse::<Ident>()?;
+            }
+            if enumerate != index.is_some() {
+                let msg = "expected `(I, T) in enumerate ...` to bind an index and a value";
+                return Err(syn::Error::new(ident.span(), msg));
+            }
+            let values = if input.peek(token::Bracket) {
+                if let Some(ty) = &ty {
+                    let msg = "a type annotation is only allowed on a range";
+                    return Err(syn::Error::new(ty.span(), msg));
+                }
+                parse_list(input)?.into_iter().map(Value::Tokens).collect()
+            } else {
+                let begin = input.cursor();
+                let range = parse_range(input, &mut ty)?;
+                if let Some(ty) = &ty {
+                    check_range(ty, &range)?;
+                }
+                if range.len > MAX_VALUES {
+                    let msg = format!("range has {} values, more than the {} supported by seq!", range.len, MAX_VALUES);
+                    return Err(syn::Error::new_spanned(tokens_between(begin, input.cursor()), msg));
+                }
+                (0..range.len).map(|k| Value::Int(range.nth(k))).collect()
+            };
+            vars.push(Var { ident, index, values, ty });
+            if input.parse::<Option<Token![,]>>()?.is_none() {
+                break;
+            }
+        }
+        // Every combination of values is a copy of the body.
+        let copies = vars.iter().fold(1u128, |copies, var| copies.saturating_mul(var.values.len() as u128));
+        if copies > MAX_VALUES {
+            let msg = format!("the variables have {} combinations of values, more than the {} supported by seq!", copies, MAX_VALUES);
+            return Err(syn::Error::new_spanned(tokens_between(header, input.cursor()), msg));
+        }
+        let content;
+        braced!(content in input);
+        let body = content.parse()?;
+        Ok(Seq { vars, body })
+    }
+}
+
+/// The tokens from `begin` up to `end`, for an error spanning them.
+fn tokens_between(begin: Cursor, end: Cursor) -> TokenStream2 {
+    let mut tokens = TokenStream2::new();
+    let mut cursor = begin;
+    while cursor < end {
+        let (token, next) = cursor.token_tree().unwrap();
+        tokens.extend([token]);
+        cursor = next;
+    }
+    tokens
+}
+
+/// Parses a bracketed list of items separated by commas. Commas inside
+/// `<...>`, as in `HashMap<K, V>`, do not separate items.
+fn parse_list(input: ParseStream) -> syn::Result<Vec<TokenStream2>> {
+    let content;
+    bracketed!(content in input);
+    let tokens: TokenStream2 = content.parse()?;
+    let mut items = Vec::new();
+    let mut item = TokenStream2::new();
+    let mut depth = 0usize;
+    let mut arrow = false;
+    for token in tokens {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                ',' if depth == 0 => {
+                    if item.is_empty() {
+                        return Err(syn::Error::new(punct.span(), "expected an item before `,`"));
+                    }
+                    items.push(std::mem::take(&mut item));
+                    continue;
+                }
+                '<' => depth += 1,
+                '>' if !arrow => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            // The `>` of `->` and `=>` does not close an angle bracket.
+            arrow = punct.spacing() == Spacing::Joint && matches!(punct.as_char(), '-' | '=');
+        } else {
+            arrow = false;
+        }
+        item.extend([token]);
+    }
+    if !item.is_empty() {
+        items.push(item);
+    }
+    Ok(items)
+}
+
+/// Most values a range may have; each one is a copy of the body.
+const MAX_VALUES: u128 = 1 << 16;
+
+/// The values of a range in order: `len` values from `start`, `step` apart
+/// and going down if `descending`. Ranges are kept in this form so that a
+/// large range is never enumerated before it is stepped.
+struct Progression {
+    start: i128,
+    step: u128,
+    descending: bool,
+    len: u128,
+}
+
+impl Progression {
+    /// The `k`th value. Every value lies between the bounds of the range, so
+    /// wrapping arithmetic gives the exact result.
+    fn nth(&self, k: u128) -> i128 {
+        let offset = k.wrapping_mul(self.step) as i128;
+        if self.descending {
+            self.start.wrapping_sub(offset)
+        } else {
+            self.start.wrapping_add(offset)
+        }
+    }
+
+    fn rev(self) -> Self {
+        match self.len {
+            0 => self,
+            len => Progression {
+                start: self.nth(len - 1),
+                descending: !self.descending,
+                ..self
+            },
+        }
+    }
+
+    fn step_by(self, step: u128) -> Self {
+        Progression {
+            // Saturating only once at most one value is left.
+            step: self.step.saturating_mul(step),
+            len: self.len.div_ceil(step),
+            ..self
+        }
+    }
+}
+
+/// Parses `LO..HI` or `LO..=HI`, optionally followed by `step S`, or a
+/// parenthesized range followed by any number of `.step_by(S)` and `.rev()`
+/// calls. The suffix of the bounds, if any, must agree with `ty` and is
+/// stored into it.
+fn parse_range(input: ParseStream, ty: &mut Option<Ident>) -> syn::Result<Progression> {
+    if input.peek(token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        let mut values = parse_range(&content, ty)?;
+        while input.peek(Token![.]) {
+            input.parse::<Token![.]>()?;
+            let method: Ident = input.parse()?;
+            let args;
+            parenthesized!(args in input);
+            if method == "rev" && args.is_empty() {
+                values = values.rev();
+            } else if method == "step_by" {
+                values = values.step_by(parse_step(&args)?);
+            } else {
+                return Err(syn::Error::new(method.span(), "expected `step_by(...)` or `rev()`"));
+            }
+        }
+        return Ok(values);
+    }
+
+    let lo_span = input.span();
+    let lo = parse_typed_bound(input, ty, "start")?;
+    let inclusive = input.peek(Token![..=]);
+    if inclusive {
+        input.parse::<Token![..=]>()?;
+    } else {
+        input.parse::<Token![..]>()?;
+    }
+    let hi_span = input.span();
+    let hi = parse_typed_bound(input, ty, "end")?;
+    if lo > hi {
+        // Spanned from the start to the end of the range.
+        let range: TokenStream2 = [Ident::new("lo", lo_span), Ident::new("hi", hi_span)]
+            .into_iter()
+            .map(TokenTree::Ident)
+            .collect();
+        let limits = if inclusive { "..=" } else { ".." };
+        let msg = format!("empty range {}{}{}", lo, limits, hi);
+        return Err(syn::Error::new_spanned(range, msg));
+    }
+    // The length of `i128::MIN..=i128::MAX` does not fit in a `u128`, but
+    // any length past `MAX_VALUES` is rejected all the same.
+    let len = hi.abs_diff(lo).saturating_add(u128::from(inclusive));
+    let values = Progression { start: lo, step: 1, descending: false, len };
+    if input.peek(syn::Ident) {
+        let step: Ident = input.parse()?;
+        if step != "step" {
+            return Err(syn::Error::new(step.span(), "expected `step` or `{`"));
+        }
+        return Ok(values.step_by(parse_step(input)?));
+    }
+    Ok(values)
+}
+
+/// Parses the `S` of `step S` or `.step_by(S)`.
+fn parse_step(input: ParseStream) -> syn::Result<u128> {
+    let span = input.span();
+    let step = parse_bound(input)?.value;
+    if step <= 0 {
+        return Err(syn::Error::new(span, "step must be positive"));
+    }
+    Ok(step as u128)
+}
+
+/// Parses the start or end of the range, as given by `which`, whose suffix, if
+/// any, must agree with `ty`.
+fn parse_typed_bound(input: ParseStream, ty: &mut Option<Ident>, which: &str) -> syn::Result<i128> {
+    let invisible = matches!(
+        input.fork().parse::<Option<TokenTree>>()?,
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::None,
+    );
+    let constant = input.peek(syn::Ident) && input.peek2(Token![::]);
+    if input.peek(syn::Ident) && !constant {
+        let ident: Ident = input.fork().parse()?;
+        let msg = format!(
+            "cannot evaluate `{}`; the bounds of seq! must be integer literals \
+             or constants like `u8::BITS`, use seq_const! for other constants",
+            ident,
+        );
+        return Err(syn::Error::new(ident.span(), msg));
+    }
+    if !(input.peek(LitInt) || input.peek(Token![-]) || constant || invisible) {
+        return Err(input.error(format!("range {} must be an integer literal or a constant like `u8::BITS`", which)));
+    }
+    let bound = parse_bound(input)?;
+    if let Some(suffix) = bound.suffix {
+        match ty {
+            Some(ty) if *ty != suffix => {
+                let msg = format!("mismatched types: expected `{}`, found `{}`", ty, suffix);
+                return Err(syn::Error::new(suffix.span(), msg));
+            }
+            Some(_) => {}
+            None => *ty = Some(suffix),
+        }
+    }
+    Ok(bound.value)
+}
+
+/// Parses a possibly negative integer bound of the range, either a literal or
+/// an integer constant of the standard library like `u8::BITS`. When `seq!` is
+/// invoked by a `macro_rules!` macro, a `$n:literal` fragment arrives wrapped
+/// in an invisible group, which is looked through.
+fn parse_bound(input: ParseStream) -> syn::Result<Bound> {
+    if let Some(TokenTree::Group(group)) = input.fork().parse::<Option<TokenTree>>()? {
+        if group.delimiter() == Delimiter::None {
+            input.parse::<TokenTree>()?;
+            return syn::parse2(group.stream());
+        }
+    }
+    input.parse()
+}
+
+struct Bound {
+    value: i128,
+    /// The suffix of the literal, spanned like it.
+    suffix: Option<Ident>,
+}
+
+impl Parse for Bound {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let minus = input.parse::<Option<Token![-]>>()?;
+        // `bound` holds the tokens after the minus sign, for the error.
+        let negate = |value: i128, bound: TokenStream2| match minus {
+            None => Ok(value),
+            Some(minus) => value.checked_neg().ok_or_else(|| {
+                let msg = format!("negating {} overflows `i128`", value);
+                syn::Error::new_spanned(quote!(#minus #bound), msg)
+            }),
+        };
+        if input.peek(syn::Ident) && input.peek2(Token![::]) {
+            let ty: Ident = input.parse()?;
+            input.parse::<Token![::]>()?;
+            let name: Ident = input.parse()?;
+            let value = match builtin_const(&ty.to_string(), &name.to_string()) {
+                Some(value) => value,
+                None => {
+                    let msg = format!(
+                        "cannot evaluate `{}::{}`; the bounds of seq! must be integer literals \
+                         or constants like `u8::BITS`, use seq_const! for other constants",
+                        ty, name,
+                    );
+                    return Err(syn::Error::new(ty.span().join(name.span()).unwrap_or(ty.span()), msg));
+                }
+            };
+            let value = negate(value, quote!(#ty::#name))?;
+            return Ok(Bound { value, suffix: None });
+        }
+        let lit: LitInt = input.parse()?;
+        let value: i128 = lit.base10_parse()?;
+        let suffix = match lit.suffix() {
+            "" => None,
+            suffix => Some(Ident::new(suffix, lit.span())),
+        };
+        let value = negate(value, quote!(#lit))?;
+        Ok(Bound { value, suffix })
+    }
+}
+
+/// The value of the integer constant `ty::name` of the standard library, for
+/// the `BITS`, `MIN` and `MAX` constants of fixed-size integer types.
+fn builtin_const(ty: &str, name: &str) -> Option<i128> {
+    let (min, max) = int_range(ty).filter(|_| ty != "isize" && ty != "usize")?;
+    match name {
+        "BITS" => Some(ty[1..].parse().unwrap()),
+        "MIN" => Some(min),
+        "MAX" if ty != "u128" => Some(max),
+        _ => None,
+    }
+}
+
+/// The range of values of an integer type, limited to `i128` for `u128` and
+/// assuming 64 bits for `isize` and `usize`.
+fn int_range(ty: &str) -> Option<(i128, i128)> {
+    Some(match ty {
+        "i8" => (i8::MIN as i128, i8::MAX as i128),
+        "i16" => (i16::MIN as i128, i16::MAX as i128),
+        "i32" => (i32::MIN as i128, i32::MAX as i128),
+        "i64" | "isize" => (i64::MIN as i128, i64::MAX as i128),
+        "i128" => (i128::MIN, i128::MAX),
+        "u8" => (0, u8::MAX as i128),
+        "u16" => (0, u16::MAX as i128),
+        "u32" => (0, u32::MAX as i128),
+        "u64" | "usize" => (0, u64::MAX as i128),
+        "u128" => (0, i128::MAX),
+        _ => return None,
+    })
+}
+
+/// Checks that every value of a range typed `ty` fits in that type, reporting
+/// the first value that does not.
+fn check_range(ty: &Ident, range: &Progression) -> syn::Result<()> {
+    let (min, max) = match int_range(&ty.to_string()) {
+        Some(range) => range,
+        None => return Err(syn::Error::new(ty.span(), "expected an integer type")),
+    };
+    if range.len == 0 {
+        return Ok(());
+    }
+    let first = range.nth(0);
+    let last = range.nth(range.len - 1);
+    let value = if first < min || first > max {
+        first
+    } else if last < min || last > max {
+        // The first value past the bound that the range crosses.
+        let bound = if last > max { max } else { min };
+        range.nth(bound.abs_diff(first) / range.step + 1)
+    } else {
+        return Ok(());
+    };
+    let msg = format!("range overflows `{}`: {} is out of range", ty, value);
+    Err(syn::Error::new(ty.span(), msg))
+}
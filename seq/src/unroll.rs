@@ -0,0 +1,86 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, Expr, RangeLimits, Token, Type};
+
+/// Maximum number of iterations unrolled by `seq_const!`.
+const LIMIT: usize = 64;
+
+/// The input of `seq_const!`: `N in LO..HI { ... }` or
+/// `N: TYPE in LO..=HI { ... }`, the bounds being constant expressions.
+pub struct SeqConst {
+    var: Ident,
+    ty: Type,
+    lo: Expr,
+    hi: Expr,
+    inclusive: bool,
+    body: TokenStream2,
+}
+
+impl Parse for SeqConst {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let var = input.parse()?;
+        let ty = match input.parse::<Option<Token![:]>>()? {
+            Some(_) => input.parse()?,
+            None => syn::parse_quote!(usize),
+        };
+        input.parse::<Token![in]>()?;
+        let range = match input.call(Expr::parse_without_eager_brace)? {
+            Expr::Range(range) => range,
+            expr => return Err(syn::Error::new_spanned(expr, "expected a range `LO..HI` or `LO..=HI`")),
+        };
+        let inclusive = matches!(range.limits, RangeLimits::Closed(_));
+        let (lo, hi) = match (range.from, range.to) {
+            (Some(lo), Some(hi)) => (*lo, *hi),
+            _ => return Err(syn::Error::new_spanned(range.limits, "expected both bounds of the range")),
+        };
+        let content;
+        braced!(content in input);
+        let body = content.parse()?;
+        Ok(SeqConst { var, ty, lo, hi, inclusive, body })
+    }
+}
+
+impl SeqConst {
+    /// Unrolls the body `LIMIT` times, each copy in its own block defining
+    /// the variable as a `const` and guarded by a condition that is constant
+    /// too, so that the copies past the end of the range never run. Those
+    /// copies are still type-checked, and constants in them evaluated, so
+    /// they define the variable as the start of the range rather than as a
+    /// value past its end. A constant assertion rejects ranges longer than
+    /// `LIMIT`.
+    pub fn expand(&self) -> TokenStream2 {
+        let SeqConst { var, ty, lo, hi, body, .. } = self;
+        let len = if self.inclusive {
+            quote!((((#hi) as #ty - (#lo) as #ty) as usize + 1))
+        } else {
+            quote!((((#hi) as #ty - (#lo) as #ty) as usize))
+        };
+        let msg = format!(
+            "seq_const! requires the start of the range not to exceed its end \
+             and unrolls at most {} iterations",
+            LIMIT,
+        );
+        let iterations = (0..LIMIT).map(|i| {
+            quote! {
+                {
+                    #[allow(dead_code, non_upper_case_globals)]
+                    const #var: #ty = if #i < #len {
+                        (#lo) as #ty + #i as #ty
+                    } else {
+                        (#lo) as #ty
+                    };
+                    if #i < #len {
+                        #body
+                    }
+                }
+            }
+        });
+        quote! {
+            {
+                const _: () = ::core::assert!((#lo) as #ty <= (#hi) as #ty && #len <= #LIMIT, #msg);
+                #(#iterations)*
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// The range may be stepped and reversed. `LO..HI step S` and the iterator
+// style `(LO..HI).step_by(S)` both keep every Sth value, and `.rev()` walks
+// the range backwards; the adapters apply in the order they are written, as
+// they would on an iterator. Bounds may be negative, in which case the
+// variable expands to a negated literal, which behaves as a single value:
+// `N.abs()` and `N as u8` apply to the negative number.
+//
+// Only the values left after stepping count, so a coarse step over a huge
+// range is fine.
+//
+// Stepping makes it easy to unroll accesses to strided register blocks, and
+// reversing to write countdowns, without filtering the values in the body.
+
+use seq::seq;
+
+const OFFSETS: [usize; 8] = seq!(N in 0..64 step 8 { [#(N,)*] });
+
+const STRIDED: [usize; 8] = seq!(N in (0..64).step_by(8) { [#(N,)*] });
+
+const COUNTDOWN: [u8; 5] = seq!(N in (1..=5).rev() { [#(N,)*] });
+
+const EVERY_THIRD_DOWN: [u8; 4] = seq!(N in (0..10).step_by(3).rev() { [#(N,)*] });
+
+const SIXTEENTHS: [u64; 16] = seq!(N in 0..u64::MAX step 0x1000_0000_0000_0000 { [#(N,)*] });
+
+const TEMPERATURES: [i8; 5] = seq!(N in -2..=2 { [#(N * 10,)*] });
+
+const MAGNITUDES: [i32; 3] = seq!(N: i32 in -3..0 { [#(N.abs(),)*] });
+
+const SQUARES: [i32; 3] = seq!(N: i32 in -3..0 { [#(N.pow(2),)*] });
+
+const WRAPPED: [u8; 2] = seq!(N: i32 in -2..0 { [#(N as u8,)*] });
+
+seq!(N in (0..4).rev() {
+    fn reg~N() -> u32 {
+        N * 4
+    }
+});
+
+fn scaled_sign(value: i32) -> i32 {
+    seq!(N in -1..=1 {
+        match value.signum() {
+            #(
+                N => N * 100,
+            )*
+            _ => unreachable!(),
+        }
+    })
+}
+
+fn main() {
+    assert_eq!(OFFSETS, [0, 8, 16, 24, 32, 40, 48, 56]);
+    assert_eq!(STRIDED, OFFSETS);
+    assert_eq!(COUNTDOWN, [5, 4, 3, 2, 1]);
+    assert_eq!(EVERY_THIRD_DOWN, [9, 6, 3, 0]);
+    assert_eq!(SIXTEENTHS[1], 1 << 60);
+    assert_eq!(SIXTEENTHS[15], 15 << 60);
+    assert_eq!(TEMPERATURES, [-20, -10, 0, 10, 20]);
+    assert_eq!(MAGNITUDES, [3, 2, 1]);
+    assert_eq!(SQUARES, [9, 4, 1]);
+    assert_eq!(WRAPPED, [254, 255]);
+    assert_eq!(reg0() + reg1() + reg2() + reg3(), 24);
+    assert_eq!(scaled_sign(-5), -100);
+    assert_eq!(scaled_sign(0), 0);
+    assert_eq!(scaled_sign(7), 100);
+}
@@ -0,0 +1,54 @@
+// Several loop variables separated by commas iterate over every combination
+// of their values, the first variable varying slowest. Paste chains such as
+// `cell_~R~_~C` join every piece, substituting each variable.
+//
+// An unlabelled `#( ... )*` section repeats over all combinations. A section
+// labelled with a variable, `#R( ... )*`, repeats over that variable alone,
+// which allows sections for different variables to be nested, for example to
+// build a two-dimensional table.
+//
+// Invocations of seq! may also be nested. The outer invocation substitutes its
+// variables in the inner body and leaves the inner invocation's unlabelled
+// sections alone.
+
+use seq::seq;
+
+seq!(R in 0..2, C in 0..3 {
+    fn cell_~R~_~C() -> u32 {
+        R * 10 + C
+    }
+});
+
+const TABLE: [[u32; 3]; 2] = seq!(R in 0..2, C in 0..3 {
+    [
+        #R(
+            [#C(R * 10 + C,)*],
+        )*
+    ]
+});
+
+const FLAT: [(u8, u8); 4] = seq!(R in 0..2, C in 0..2 { [#((R, C),)*] });
+
+seq!(R in 0..3 {
+    #(
+        seq!(C in 0..3 {
+            const ROW~R: [u32; 3] = [#(R * 10 + C,)*];
+        });
+    )*
+});
+
+seq!(R in 0..2 {
+    seq!(C in 0..2 {
+        fn nested_~R~_~C() -> u32 {
+            R * 10 + C
+        }
+    });
+});
+
+fn main() {
+    assert_eq!(cell_0_0() + cell_0_2() + cell_1_1(), 13);
+    assert_eq!(TABLE, [[0, 1, 2], [10, 11, 12]]);
+    assert_eq!(FLAT, [(0, 0), (0, 1), (1, 0), (1, 1)]);
+    assert_eq!([ROW0, ROW1, ROW2][2], [20, 21, 22]);
+    assert_eq!(nested_1_0(), 10);
+}
@@ -0,0 +1,38 @@
+// Inside the body, `#{ ... }` evaluates an integer expression of the loop
+// variables at compile time and expands to the resulting literal. The
+// operators `+ - * / % << >>`, unary minus and parentheses are supported, with
+// the usual precedence; overflow and division by zero are compile errors.
+//
+// Unlike a const expression, an evaluated expression can also be a piece of a
+// paste chain, which makes names like `field_3_to_4` possible.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    fn field_~N~_to_~#{N + 1}() -> u32 {
+        #{N * 4 + 1}
+    }
+
+    const MASK_~#{1 << N}: u8 = #{1 << N};
+});
+
+const OFFSETS: [i32; 4] = seq!(N in 0..4 { [#(#{(N - 2) * -3 % 5},)*] });
+
+const BLOCKS: [u8; 3] = seq!(N in 0..3 {
+    [#(#{(N + 1) * 32 / 3 >> 1},)*]
+});
+
+seq!(R in 0..2, C in 0..2 {
+    #(
+        const CELL_~#{R * 2 + C}: (u8, u8) = (R, C);
+    )*
+});
+
+fn main() {
+    assert_eq!(field_0_to_1(), 1);
+    assert_eq!(field_3_to_4(), 13);
+    assert_eq!(MASK_1 | MASK_2 | MASK_4 | MASK_8, 15);
+    assert_eq!(OFFSETS, [1, 3, 0, -3]);
+    assert_eq!(BLOCKS, [5, 10, 16]);
+    assert_eq!(CELL_2, (1, 0));
+}
@@ -0,0 +1,23 @@
+// The variable normally expands to an unsuffixed literal whose type is
+// inferred from where it is used. Where nothing constrains it, such as in a
+// method call, the literal needs a type. If the bounds of the range carry a
+// suffix, as in `0u16..8u16`, every substituted literal carries it too.
+// Alternatively the variable can be annotated with a type, `N: u8 in 0..8`.
+
+use seq::seq;
+
+const ONES: [u32; 4] = seq!(N in 0u16..4u16 { [#(N.count_ones(),)*] });
+
+const LEADING: [u32; 3] = seq!(N: u8 in 1..=3 { [#(N.leading_zeros(),)*] });
+
+seq!(N: i64 in -1..=1 {
+    fn signum~#{N + 1}() -> i64 {
+        N.signum()
+    }
+});
+
+fn main() {
+    assert_eq!(ONES, [0, 1, 1, 2]);
+    assert_eq!(LEADING, [7, 6, 6]);
+    assert_eq!(signum0() + signum1() + signum2(), 0);
+}
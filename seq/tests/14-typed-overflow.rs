@@ -0,0 +1,10 @@
+// Every value of a typed range must fit in its type. This is a compile_fail
+// test.
+
+use seq::seq;
+
+seq!(N: u8 in 250..260 {
+    const _: u8 = N;
+});
+
+fn main() {}
@@ -0,0 +1,51 @@
+// A paste piece can also be a formatted value, `{EXPR:SPEC}`, where the spec
+// has the form `[#][0][width][x|X|o|b]` as in format!. This produces names
+// like `REG_0F` or `Irq007` that match a datasheet.
+//
+// Placeholders `{N}` and `{EXPR:SPEC}` in string literals are replaced by the
+// formatted value as well. Placeholders that do not involve a variable of
+// seq!, such as those of a format string, are left alone. So are escaped
+// braces, which therefore stay doubled in a string that is not passed to
+// format!.
+
+use seq::seq;
+
+seq!(N in 0..16 {
+    const REG_~{N:02X}: u8 = N;
+});
+
+seq!(N in 0..8 {
+    #[derive(Debug, PartialEq)]
+    enum Irq {
+        #(
+            Irq~{N:03},
+        )*
+    }
+});
+
+seq!(N in 0..4 {
+    fn field_~{N * 8:02}~_~{N * 8 + 7:02}() -> &'static str {
+        "bits {N * 8}..={N * 8 + 7} of register {N:#04x}"
+    }
+});
+
+fn describe(value: u32) -> String {
+    seq!(N in 1..2 {
+        format!("{} is field {N} ({{N}})", value)
+    })
+}
+
+fn mask() -> &'static str {
+    seq!(N in 3..4 {
+        "mask {{N}} of bit {N}"
+    })
+}
+
+fn main() {
+    assert_eq!(REG_0A + REG_0F, 25);
+    assert_eq!(format!("{:?}", Irq::Irq007), "Irq007");
+    assert_eq!(field_08_15(), "bits 8..=15 of register 0x01");
+    assert_eq!(field_24_31(), "bits 24..=31 of register 0x03");
+    assert_eq!(describe(5), "5 is field 1 ({N})");
+    assert_eq!(mask(), "mask {{N}} of bit 3");
+}
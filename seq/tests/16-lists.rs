@@ -0,0 +1,51 @@
+// Besides ranges, a variable can iterate over a bracketed list of items, such
+// as types. Each item is substituted as written, and can be pasted onto an
+// identifier or interpolated into a string literal like a number. Commas
+// inside `<...>` do not separate items.
+//
+// `(I, T) in enumerate [...]` binds a second variable to the position of each
+// item, which is useful to generate ids or indices alongside the items.
+//
+// This replaces the usual `macro_rules!` written only to implement a trait
+// for every integer type.
+
+use seq::seq;
+use std::collections::HashMap;
+
+trait Width {
+    const BITS: u32;
+    const NAME: &'static str;
+}
+
+seq!(T in [u8, u16, u32, u64] {
+    impl Width for T {
+        const BITS: u32 = T::BITS;
+        const NAME: &'static str = "{T}";
+    }
+
+    fn max_~T() -> T {
+        T::MAX
+    }
+});
+
+seq!((I, T) in enumerate [bool, char, HashMap<String, Vec<u8>>] {
+    #(
+        const ID_~I: (usize, &str) = (I, stringify!(T));
+
+        fn default_~I() -> T {
+            Default::default()
+        }
+    )*
+});
+
+const SQUARES: [i32; 3] = seq!((I, N) in enumerate (3..6).rev() { [#(I * 100 + N * N,)*] });
+
+fn main() {
+    assert_eq!(<u16 as Width>::BITS, 16);
+    assert_eq!(<u64 as Width>::NAME, "u64");
+    assert_eq!(max_u8(), 255);
+    assert_eq!(ID_0, (0, "bool"));
+    assert_eq!(ID_2.0, 2);
+    assert!(default_2().is_empty());
+    assert_eq!(SQUARES, [25, 116, 209]);
+}
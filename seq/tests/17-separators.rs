@@ -0,0 +1,43 @@
+// Like in macro_rules!, punctuation between the parenthesized group and the
+// `*` of a repeated section is a separator, emitted between repetitions but
+// not after the last one: `#( ... ),*`, `#( ... );*`, `#( ... )+*` or
+// multi-character operators such as `#( ... )&&*`.
+//
+// This builds expressions like `a0 + a1 + a2` or `a0 && a1 && a2`, where a
+// trailing separator would be an error.
+
+use seq::seq;
+
+fn sum(a: [u32; 4]) -> u32 {
+    seq!(N in 0..4 { #(a[N])+* })
+}
+
+fn all_even(a: [u32; 3]) -> bool {
+    seq!(N in 0..3 { #(a[N] % 2 == 0)&&* })
+}
+
+fn single() -> (u8,) {
+    // A trailing comma would be required for a one-element tuple, so the
+    // separator gives a parenthesized expression instead.
+    seq!(N in 0..1 { (#(N),*,) })
+}
+
+seq!(N in 0..3 {
+    fn tuple() -> (u8, u16, u32) {
+        (#(N * 10),*)
+    }
+
+    fn statements() -> u32 {
+        let mut total = 0;
+        #(total += N);*;
+        total
+    }
+});
+
+fn main() {
+    assert_eq!(sum([1, 2, 3, 4]), 10);
+    assert!(all_even([2, 4, 6]));
+    assert!(!all_even([2, 3, 6]));
+    assert_eq!(tuple(), (0, 10, 20));
+    assert_eq!(statements(), 3);
+}
@@ -0,0 +1,55 @@
+// Besides literals, the bounds of seq! may be the `BITS`, `MIN` and `MAX`
+// constants of the fixed-size integer types, whose values are known to the
+// macro.
+//
+// Other constants, such as `const` items, cannot be evaluated by a macro. For
+// those, seq_const! unrolls a loop in statement position instead: the body is
+// copied a fixed number of times, each copy defining the variable as a `const`
+// and guarded by a constant condition, so that the copies past the end of the
+// range never run. Because the variable is a `const`, it can be used as an
+// array length or a const generic argument.
+//
+// The copies past the end are still compiled. They see the start of the range
+// as the value of the variable, so a body that is valid for every value of
+// the range, like `[0u8; LEN - N]`, compiles even though `LEN - N` would
+// overflow past the end.
+
+use seq::{seq, seq_const};
+
+const BIT_MASKS: [u8; 8] = seq!(N in 0..u8::BITS { [#(1 << N,)*] });
+
+const LEN: usize = 5;
+
+fn nth<const N: usize>(array: &[u32; LEN]) -> u32 {
+    array[N]
+}
+
+fn main() {
+    assert_eq!(BIT_MASKS[7], 128);
+
+    let array = [1, 2, 3, 4, 5];
+    let mut sum = 0;
+    seq_const!(N in 0..LEN {
+        sum += nth::<N>(&array);
+    });
+    assert_eq!(sum, 15);
+
+    let mut squares = Vec::new();
+    seq_const!(N: u32 in 2..=LEN as u32 - 1 {
+        squares.push(N * N);
+    });
+    assert_eq!(squares, [4, 9, 16]);
+
+    let mut zeroes = 0;
+    seq_const!(N in 1..LEN {
+        zeroes += [0u8; N].len();
+    });
+    assert_eq!(zeroes, 1 + 2 + 3 + 4);
+
+    let mut remaining = Vec::new();
+    seq_const!(N in 0..LEN {
+        let a = [0u8; LEN - N];
+        remaining.push(a.len());
+    });
+    assert_eq!(remaining, [5, 4, 3, 2, 1]);
+}
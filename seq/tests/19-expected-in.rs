@@ -0,0 +1,10 @@
+// Errors in the header of seq! are reported at the offending token, with the
+// message of the parser. This is a compile_fail test.
+
+use seq::seq;
+
+seq!(N of 0..4 {
+    fn f~N() {}
+});
+
+fn main() {}
@@ -0,0 +1,14 @@
+// The bounds of the range must be integer literals or constants like
+// `u8::BITS`, which the macro can evaluate. A `const` item is a compile error
+// at the bound; seq_const! unrolls loops over such ranges. This is a
+// compile_fail test.
+
+use seq::seq;
+
+const LEN: usize = 4;
+
+seq!(N in 0..LEN {
+    fn f~N() {}
+});
+
+fn main() {}
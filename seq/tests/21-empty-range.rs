@@ -0,0 +1,11 @@
+// A range whose start exceeds its end is most likely a mistake for a
+// descending range, which is written `(3..5).rev()`. It is a compile error
+// spanning the range. This is a compile_fail test.
+
+use seq::seq;
+
+seq!(N in 5..3 {
+    fn f~N() {}
+});
+
+fn main() {}
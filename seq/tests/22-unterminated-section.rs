@@ -0,0 +1,16 @@
+// A repeated section must end with `*`, possibly after a separator. Without
+// it, the `#( ... )` would be emitted as is and fail to parse somewhere in the
+// generated code, so it is reported at the section instead. This is a
+// compile_fail test.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    enum Interrupt {
+        #(
+            Irq~N,
+        )
+    }
+});
+
+fn main() {}
@@ -0,0 +1,10 @@
+// `~` pastes a variable of seq! onto an identifier. Pasting anything else is
+// reported at the token following the `~`. This is a compile_fail test.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    fn f~M() {}
+});
+
+fn main() {}
@@ -0,0 +1,11 @@
+// Every value of the range is a copy of the body, so a range of more than
+// 65536 values, counted after stepping, is a compile error rather than an
+// attempt to expand it. This is a compile_fail test.
+
+use seq::seq;
+
+seq!(N in 0..u32::MAX step 2 {
+    fn f~N() {}
+});
+
+fn main() {}
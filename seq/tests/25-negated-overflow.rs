@@ -0,0 +1,10 @@
+// Negating `i128::MIN` overflows, so it cannot be a bound of the range. The
+// error spans the negated constant. This is a compile_fail test.
+
+use seq::seq;
+
+seq!(N in -i128::MIN..0 {
+    fn f~N() {}
+});
+
+fn main() {}
@@ -0,0 +1,18 @@
+// seq_const! defines the variable of every copy of the body as a `const` item
+// computed from the bounds, and items cannot use the generic parameters of the
+// enclosing function. So the bounds cannot mention a const generic parameter,
+// only constants such as `const` items. This is a compile_fail test.
+
+use seq::seq_const;
+
+fn sum<const LEN: usize>(array: [u32; LEN]) -> u32 {
+    let mut sum = 0;
+    seq_const!(N in 0..LEN {
+        sum += array[N];
+    });
+    sum
+}
+
+fn main() {
+    assert_eq!(sum([1, 2, 3]), 6);
+}
@@ -0,0 +1,11 @@
+// Each variable may take at most 65536 values, and so may the combinations of
+// values of several variables, since each one is a copy of the body. The
+// error spans the header of the invocation. This is a compile_fail test.
+
+use seq::seq;
+
+seq!(A in 0..60000, B in 0..60000 {
+    fn f~A~_~B() {}
+});
+
+fn main() {}
@@ -1,13 +1,31 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse-header.rs");
-    //t.pass("tests/02-parse-body.rs");
-    //t.compile_fail("tests/03-expand-four-errors.rs");
-    //t.pass("tests/04-paste-ident.rs");
-    //t.pass("tests/05-repeat-section.rs");
-    //t.pass("tests/06-init-array.rs");
-    //t.pass("tests/07-inclusive-range.rs");
-    //t.compile_fail("tests/08-ident-span.rs");
-    //t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/01-parse-header.rs");
+    t.pass("tests/02-parse-body.rs");
+    t.compile_fail("tests/03-expand-four-errors.rs");
+    t.pass("tests/04-paste-ident.rs");
+    t.pass("tests/05-repeat-section.rs");
+    t.pass("tests/06-init-array.rs");
+    t.pass("tests/07-inclusive-range.rs");
+    t.compile_fail("tests/08-ident-span.rs");
+    t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/10-stepped-and-reversed.rs");
+    t.pass("tests/11-multiple-variables.rs");
+    t.pass("tests/12-arithmetic.rs");
+    t.pass("tests/13-typed-literals.rs");
+    t.compile_fail("tests/14-typed-overflow.rs");
+    t.pass("tests/15-formatted-pastes.rs");
+    t.pass("tests/16-lists.rs");
+    t.pass("tests/17-separators.rs");
+    t.pass("tests/18-const-bounds.rs");
+    t.compile_fail("tests/19-expected-in.rs");
+    t.compile_fail("tests/20-bound-not-literal.rs");
+    t.compile_fail("tests/21-empty-range.rs");
+    t.compile_fail("tests/22-unterminated-section.rs");
+    t.compile_fail("tests/23-paste-without-variable.rs");
+    t.compile_fail("tests/24-too-many-values.rs");
+    t.compile_fail("tests/25-negated-overflow.rs");
+    t.compile_fail("tests/26-const-generic-bound.rs");
+    t.compile_fail("tests/27-too-many-combinations.rs");
 }